@@ -0,0 +1,9 @@
+CREATE TABLE BlacklistEntries(
+    id INTEGER UNIQUE NOT NULL AUTO_INCREMENT,
+    pattern TEXT NOT NULL,
+    added_by BIGINT UNSIGNED NOT NULL,
+    added_at DATETIME NOT NULL,
+    reason TEXT,
+    enabled BOOLEAN NOT NULL DEFAULT TRUE,
+    PRIMARY KEY (id)
+);
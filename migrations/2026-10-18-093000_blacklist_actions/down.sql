@@ -0,0 +1,5 @@
+DROP TABLE BlacklistHits;
+
+ALTER TABLE BlacklistEntries
+DROP COLUMN action,
+DROP COLUMN timeout_minutes;
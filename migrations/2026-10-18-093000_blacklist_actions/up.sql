@@ -0,0 +1,16 @@
+ALTER TABLE BlacklistEntries
+ADD COLUMN action VARCHAR(16) NOT NULL DEFAULT 'delete',
+ADD COLUMN timeout_minutes INTEGER;
+
+CREATE TABLE BlacklistHits(
+    id INTEGER UNIQUE NOT NULL AUTO_INCREMENT,
+    entry_id INTEGER NOT NULL,
+    user_id BIGINT UNSIGNED NOT NULL,
+    channel_id BIGINT UNSIGNED NOT NULL,
+    message_id BIGINT UNSIGNED NOT NULL,
+    content TEXT NOT NULL,
+    action VARCHAR(16) NOT NULL,
+    hit_time DATETIME NOT NULL,
+    PRIMARY KEY (id),
+    FOREIGN KEY (entry_id) REFERENCES BlacklistEntries (id)
+);
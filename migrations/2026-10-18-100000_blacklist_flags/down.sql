@@ -0,0 +1,4 @@
+ALTER TABLE BlacklistEntries
+DROP COLUMN case_sensitive,
+DROP COLUMN whole_word,
+DROP COLUMN normalize;
@@ -0,0 +1,4 @@
+ALTER TABLE BlacklistEntries
+ADD COLUMN case_sensitive BOOLEAN NOT NULL DEFAULT FALSE,
+ADD COLUMN whole_word BOOLEAN NOT NULL DEFAULT FALSE,
+ADD COLUMN normalize BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,7 @@
+DELETE FROM BlacklistHits WHERE user_id IS NULL OR channel_id IS NULL OR message_id IS NULL;
+
+ALTER TABLE BlacklistHits
+DROP COLUMN source,
+MODIFY user_id BIGINT UNSIGNED NOT NULL,
+MODIFY channel_id BIGINT UNSIGNED NOT NULL,
+MODIFY message_id BIGINT UNSIGNED NOT NULL;
@@ -0,0 +1,5 @@
+ALTER TABLE BlacklistHits
+MODIFY user_id BIGINT UNSIGNED,
+MODIFY channel_id BIGINT UNSIGNED,
+MODIFY message_id BIGINT UNSIGNED,
+ADD COLUMN source VARCHAR(16) NOT NULL DEFAULT 'content';
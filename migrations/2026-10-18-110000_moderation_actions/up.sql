@@ -0,0 +1,12 @@
+CREATE TABLE ModerationActions(
+    id INTEGER UNIQUE NOT NULL AUTO_INCREMENT,
+    user_id BIGINT UNSIGNED NOT NULL,
+    actor_id BIGINT UNSIGNED NOT NULL,
+    kind VARCHAR(32) NOT NULL,
+    source VARCHAR(16) NOT NULL,
+    reason TEXT,
+    action_time DATETIME NOT NULL,
+    expires_at DATETIME,
+    PRIMARY KEY (id),
+    INDEX (user_id)
+);
@@ -0,0 +1,3 @@
+DELETE FROM ModerationActions WHERE actor_id IS NULL;
+
+ALTER TABLE ModerationActions MODIFY actor_id BIGINT UNSIGNED NOT NULL;
@@ -0,0 +1,2 @@
+-- Roles changed by hand don't tell who changed them
+ALTER TABLE ModerationActions MODIFY actor_id BIGINT UNSIGNED;
@@ -0,0 +1,10 @@
+CREATE TABLE Warnings(
+    id INTEGER UNIQUE NOT NULL AUTO_INCREMENT,
+    user_id BIGINT UNSIGNED NOT NULL,
+    issuer_id BIGINT UNSIGNED NOT NULL,
+    reason TEXT NOT NULL,
+    issued_at DATETIME NOT NULL,
+    expires_at DATETIME NOT NULL,
+    PRIMARY KEY (id),
+    INDEX (user_id)
+);
@@ -0,0 +1,5 @@
+ALTER TABLE SilencedMembers
+DROP COLUMN issuer_id,
+DROP COLUMN reason,
+DROP COLUMN silenced_at,
+DROP COLUMN expires_at;
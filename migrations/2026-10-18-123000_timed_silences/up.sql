@@ -0,0 +1,5 @@
+ALTER TABLE SilencedMembers
+ADD COLUMN issuer_id BIGINT UNSIGNED,
+ADD COLUMN reason TEXT,
+ADD COLUMN silenced_at DATETIME,
+ADD COLUMN expires_at DATETIME;
@@ -0,0 +1,2 @@
+DROP TABLE AppealVotes;
+DROP TABLE Appeals;
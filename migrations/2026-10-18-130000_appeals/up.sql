@@ -0,0 +1,25 @@
+CREATE TABLE Appeals(
+    id INTEGER UNIQUE NOT NULL AUTO_INCREMENT,
+    user_id BIGINT UNSIGNED NOT NULL,
+    vote_message_id BIGINT UNSIGNED NOT NULL,
+    voting_message_id BIGINT UNSIGNED,
+    appeal_text TEXT NOT NULL,
+    created_at DATETIME NOT NULL,
+    uphold_votes INTEGER NOT NULL,
+    lift_votes INTEGER NOT NULL,
+    votes_required INTEGER NOT NULL,
+    moderators_online INTEGER NOT NULL,
+    resolution VARCHAR(16),
+    resolved_at DATETIME,
+    PRIMARY KEY (id),
+    UNIQUE (vote_message_id),
+    INDEX (user_id)
+);
+CREATE TABLE AppealVotes(
+    id INTEGER UNIQUE NOT NULL AUTO_INCREMENT,
+    appeal_id INTEGER NOT NULL,
+    voter_user_id BIGINT UNSIGNED NOT NULL,
+    vote_type INTEGER NOT NULL,
+    PRIMARY KEY (id),
+    UNIQUE (appeal_id, voter_user_id)
+);
@@ -0,0 +1,22 @@
+ALTER TABLE CouncilVotings
+    ADD COLUMN delete_votes INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN delete_votes_required INTEGER NOT NULL DEFAULT 1,
+    ADD COLUMN silence_votes INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN silence_votes_required INTEGER NOT NULL DEFAULT 1,
+    ADD COLUMN block_reporter_votes INTEGER NOT NULL DEFAULT 0,
+    ADD COLUMN block_reporter_votes_required INTEGER NOT NULL DEFAULT 1;
+UPDATE CouncilVotings c SET
+    delete_votes = (SELECT COUNT(*) FROM VotingActions v
+        WHERE v.voting_message_id = c.vote_message_id AND v.vote_type = 0),
+    silence_votes = (SELECT COUNT(*) FROM VotingActions v
+        WHERE v.voting_message_id = c.vote_message_id AND v.vote_type = 1),
+    block_reporter_votes = (SELECT COUNT(*) FROM VotingActions v
+        WHERE v.voting_message_id = c.vote_message_id AND v.vote_type = 2),
+    delete_votes_required = COALESCE((SELECT votes_required FROM CouncilVotingThresholds t
+        WHERE t.vote_message_id = c.vote_message_id AND t.vote_type = 0), 1),
+    silence_votes_required = COALESCE((SELECT votes_required FROM CouncilVotingThresholds t
+        WHERE t.vote_message_id = c.vote_message_id AND t.vote_type = 1), 1),
+    block_reporter_votes_required = COALESCE((SELECT votes_required FROM CouncilVotingThresholds t
+        WHERE t.vote_message_id = c.vote_message_id AND t.vote_type = 2), 1);
+DELETE FROM VotingActions WHERE vote_type > 2;
+DROP TABLE CouncilVotingThresholds;
@@ -0,0 +1,29 @@
+CREATE TABLE CouncilVotingThresholds(
+    id INTEGER UNIQUE NOT NULL AUTO_INCREMENT,
+    vote_message_id BIGINT UNSIGNED NOT NULL,
+    vote_type INTEGER NOT NULL,
+    votes_required INTEGER NOT NULL,
+    executed_at DATETIME,
+    PRIMARY KEY (id),
+    UNIQUE (vote_message_id, vote_type)
+);
+-- The time the action was carried out isn't known for old votings
+INSERT INTO CouncilVotingThresholds (vote_message_id, vote_type, votes_required, executed_at)
+    SELECT vote_message_id, 0, delete_votes_required,
+        IF(delete_votes >= delete_votes_required, suspect_message_send_time, NULL)
+    FROM CouncilVotings;
+INSERT INTO CouncilVotingThresholds (vote_message_id, vote_type, votes_required, executed_at)
+    SELECT vote_message_id, 1, silence_votes_required,
+        IF(silence_votes >= silence_votes_required, suspect_message_send_time, NULL)
+    FROM CouncilVotings;
+INSERT INTO CouncilVotingThresholds (vote_message_id, vote_type, votes_required, executed_at)
+    SELECT vote_message_id, 2, block_reporter_votes_required,
+        IF(block_reporter_votes >= block_reporter_votes_required, suspect_message_send_time, NULL)
+    FROM CouncilVotings;
+ALTER TABLE CouncilVotings
+    DROP COLUMN delete_votes,
+    DROP COLUMN delete_votes_required,
+    DROP COLUMN silence_votes,
+    DROP COLUMN silence_votes_required,
+    DROP COLUMN block_reporter_votes,
+    DROP COLUMN block_reporter_votes_required;
@@ -0,0 +1,5 @@
+ALTER TABLE CouncilVotings
+    DROP COLUMN deadline,
+    DROP COLUMN resolution,
+    DROP COLUMN closed_at,
+    DROP COLUMN closed_by;
@@ -0,0 +1,13 @@
+ALTER TABLE CouncilVotings
+    ADD COLUMN deadline DATETIME,
+    ADD COLUMN resolution VARCHAR(16) NOT NULL DEFAULT 'pending',
+    ADD COLUMN closed_at DATETIME,
+    ADD COLUMN closed_by BIGINT UNSIGNED;
+-- Old votings are closed as they are, instead of expiring and editing all of them at once
+UPDATE CouncilVotings c SET
+    resolution = IF(EXISTS(SELECT * FROM CouncilVotingThresholds t
+        WHERE t.vote_message_id = c.vote_message_id AND t.executed_at IS NOT NULL),
+        'actioned', 'expired'),
+    deadline = suspect_message_send_time,
+    closed_at = NOW();
+ALTER TABLE CouncilVotings MODIFY deadline DATETIME NOT NULL;
@@ -0,0 +1,4 @@
+DROP TABLE CouncilVotingReports;
+ALTER TABLE CouncilVotings
+    DROP COLUMN report_category,
+    DROP COLUMN report_reason;
@@ -0,0 +1,13 @@
+ALTER TABLE CouncilVotings
+    ADD COLUMN report_category VARCHAR(16),
+    ADD COLUMN report_reason TEXT;
+CREATE TABLE CouncilVotingReports(
+    id INTEGER UNIQUE NOT NULL AUTO_INCREMENT,
+    vote_message_id BIGINT UNSIGNED NOT NULL,
+    reporter_id BIGINT UNSIGNED NOT NULL,
+    category VARCHAR(16),
+    reason TEXT,
+    reported_at DATETIME NOT NULL,
+    PRIMARY KEY (id),
+    UNIQUE (vote_message_id, reporter_id)
+);
@@ -0,0 +1,4 @@
+DROP TABLE ReportAttachments;
+ALTER TABLE CouncilVotings
+    DROP COLUMN reply_to,
+    DROP COLUMN context;
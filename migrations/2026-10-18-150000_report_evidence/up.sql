@@ -0,0 +1,13 @@
+ALTER TABLE CouncilVotings
+    ADD COLUMN reply_to TEXT,
+    ADD COLUMN context TEXT;
+CREATE TABLE ReportAttachments(
+    id INTEGER UNIQUE NOT NULL AUTO_INCREMENT,
+    vote_message_id BIGINT UNSIGNED NOT NULL,
+    filename VARCHAR(255) NOT NULL,
+    url TEXT NOT NULL,
+    archive_path TEXT,
+    evidence_url TEXT,
+    PRIMARY KEY (id),
+    INDEX (vote_message_id)
+);
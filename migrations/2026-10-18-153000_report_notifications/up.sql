@@ -0,0 +1,5 @@
+CREATE TABLE ReportNotifications(
+    user_id BIGINT UNSIGNED NOT NULL,
+    subscribed_at DATETIME NOT NULL,
+    PRIMARY KEY (user_id)
+);
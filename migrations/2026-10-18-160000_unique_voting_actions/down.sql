@@ -0,0 +1,2 @@
+ALTER TABLE VotingActions
+    DROP INDEX unique_vote;
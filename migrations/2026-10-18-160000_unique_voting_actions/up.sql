@@ -0,0 +1,9 @@
+-- Votes double counted before the index existed
+DELETE a FROM VotingActions a
+    JOIN VotingActions b
+    ON a.voting_message_id = b.voting_message_id
+        AND a.voter_user_id = b.voter_user_id
+        AND a.vote_type = b.vote_type
+        AND a.id > b.id;
+ALTER TABLE VotingActions
+    ADD UNIQUE INDEX unique_vote (voting_message_id, voter_user_id, vote_type);
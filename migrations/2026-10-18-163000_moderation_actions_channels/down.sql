@@ -0,0 +1,4 @@
+DELETE FROM ModerationActions WHERE user_id IS NULL;
+ALTER TABLE ModerationActions
+    MODIFY user_id BIGINT UNSIGNED NOT NULL,
+    DROP COLUMN channel_id;
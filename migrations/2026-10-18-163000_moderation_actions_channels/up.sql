@@ -0,0 +1,4 @@
+-- Actions on channels, such as slowmode, have no member
+ALTER TABLE ModerationActions
+    MODIFY user_id BIGINT UNSIGNED,
+    ADD COLUMN channel_id BIGINT UNSIGNED;
@@ -0,0 +1 @@
+ALTER TABLE CouncilVotingThresholds DROP COLUMN result;
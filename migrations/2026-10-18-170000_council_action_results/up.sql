@@ -0,0 +1,2 @@
+-- What came of carrying out the action, e.g. that the member couldn't be messaged
+ALTER TABLE CouncilVotingThresholds ADD COLUMN result TEXT;
@@ -0,0 +1,2 @@
+DROP TABLE StickyMembers;
+DROP TABLE StickyRoles;
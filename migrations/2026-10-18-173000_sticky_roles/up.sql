@@ -0,0 +1,13 @@
+-- Punitive roles and timeouts are reapplied when the member leaves and rejoins
+CREATE TABLE StickyRoles(
+    user_id BIGINT UNSIGNED NOT NULL,
+    role_id BIGINT UNSIGNED NOT NULL,
+    PRIMARY KEY (user_id, role_id)
+);
+
+CREATE TABLE StickyMembers(
+    user_id BIGINT UNSIGNED NOT NULL,
+    timeout_until DATETIME,
+    nickname VARCHAR(32),
+    PRIMARY KEY (user_id)
+);
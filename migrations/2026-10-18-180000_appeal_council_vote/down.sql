@@ -0,0 +1,2 @@
+ALTER TABLE Appeals
+    CHANGE council_vote_message_id voting_message_id BIGINT UNSIGNED;
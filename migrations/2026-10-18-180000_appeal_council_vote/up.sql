@@ -0,0 +1,3 @@
+-- `voting_message_id` next to `vote_message_id` was too easy to mix up
+ALTER TABLE Appeals
+    CHANGE voting_message_id council_vote_message_id BIGINT UNSIGNED;
@@ -0,0 +1,6 @@
+ALTER TABLE Appeals
+    ADD COLUMN uphold_votes INTEGER NOT NULL DEFAULT 0 AFTER created_at,
+    ADD COLUMN lift_votes INTEGER NOT NULL DEFAULT 0 AFTER uphold_votes;
+UPDATE Appeals SET
+    uphold_votes = (SELECT COUNT(*) FROM AppealVotes WHERE appeal_id = Appeals.id AND vote_type = 0),
+    lift_votes = (SELECT COUNT(*) FROM AppealVotes WHERE appeal_id = Appeals.id AND vote_type = 1);
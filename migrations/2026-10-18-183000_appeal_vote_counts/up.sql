@@ -0,0 +1,4 @@
+-- The counts are derived from AppealVotes instead of being kept in sync with it
+ALTER TABLE Appeals
+    DROP COLUMN uphold_votes,
+    DROP COLUMN lift_votes;
@@ -0,0 +1,527 @@
+use serenity::{
+    builder::EditMessage,
+    model::{
+        application::{
+            component::{ActionRowComponent, ButtonStyle, InputTextStyle},
+            interaction::{
+                message_component::MessageComponentInteraction,
+                modal::ModalSubmitInteraction,
+                InteractionResponseType::{ChannelMessageWithSource, DeferredUpdateMessage, Modal},
+            },
+        },
+        id::UserId,
+        interactions::InteractionApplicationCommandCallbackDataFlags,
+    },
+};
+
+use crate::{
+    extensions::*,
+    models::{Appeal, AppealVote, CouncilVoting, NewAppeal, SilencedMember, SuspectMessageEdit},
+    moderation::{
+        action::{self, Action},
+        council::CouncilAction,
+        ActionSource,
+    },
+    voting::{filter_votes, get_online_mod_count, is_moderator, send_edit_history},
+    Context, Message, User,
+};
+
+const UPHOLD_VOTE: i32 = 0;
+const LIFT_VOTE: i32 = 1;
+
+/// The resolution of an appeal whose silence is being lifted
+const LIFTING: &str = "lifting";
+const LIFTED: &str = "lifted";
+const UPHELD: &str = "upheld";
+
+/// A DM to the bot from a silenced member opens an appeal with the message as its text.
+/// Returns `true` if the message was handled as an appeal.
+pub async fn handle_dm(ctx: &Context, msg: &Message) -> bool {
+    if msg.guild_id.is_some() || msg.author.bot {
+        return false;
+    }
+    if !ctx
+        .get_db()
+        .await
+        .is_silenced(msg.author.id.0)
+        .await
+        .unwrap_or(false)
+    {
+        return false;
+    }
+    let reply = match open_appeal(ctx, &msg.author, msg.content.clone()).await {
+        Ok(reply) => reply,
+        Err(e) => {
+            error!("Unable to open an appeal for {}: {}", msg.author.id.0, e);
+            "Valituksen lähettäminen epäonnistui, yritä myöhemmin uudelleen."
+        }
+    };
+    msg.reply(&ctx.http, reply).await.ok();
+    true
+}
+
+pub async fn handle_component_interaction(ctx: &Context, component: MessageComponentInteraction) {
+    match component.data.custom_id.as_str() {
+        "appeal_open" => show_appeal_modal(ctx, &component).await,
+        "appeal_uphold" => {
+            info!("Appeal uphold vote by {}", component.user.tag());
+            handle_appeal_vote(ctx, &component, UPHOLD_VOTE).await;
+        }
+        "appeal_lift" => {
+            info!("Appeal lift vote by {}", component.user.tag());
+            handle_appeal_vote(ctx, &component, LIFT_VOTE).await;
+        }
+        "appeal_edit_history" => {
+            let appeal = ctx.get_db().await.get_appeal(component.message.id.0).await;
+            if let Ok(Appeal {
+                council_vote_message_id: Some(voting_message_id),
+                ..
+            }) = appeal
+            {
+                send_edit_history(ctx, &component, voting_message_id).await;
+            }
+        }
+        _ => debug!("Unknown interaction: {}", component.data.custom_id),
+    }
+}
+
+pub async fn handle_modal_submit(ctx: &Context, modal: ModalSubmitInteraction) {
+    if modal.data.custom_id != "appeal_modal" {
+        debug!("Unknown modal: {}", modal.data.custom_id);
+        return;
+    }
+    let text = modal
+        .data
+        .components
+        .iter()
+        .flat_map(|row| row.components.iter())
+        .find_map(|c| match c {
+            ActionRowComponent::InputText(t) if t.custom_id == "appeal_text" => {
+                Some(t.value.clone())
+            }
+            _ => None,
+        })
+        .unwrap_or_default();
+    let reply = match open_appeal(ctx, &modal.user, text).await {
+        Ok(reply) => reply,
+        Err(e) => {
+            error!("Unable to open an appeal for {}: {}", modal.user.id.0, e);
+            "Valituksen lähettäminen epäonnistui, yritä myöhemmin uudelleen."
+        }
+    };
+    modal
+        .create_interaction_response(&ctx.http, |r| {
+            r.kind(ChannelMessageWithSource)
+                .interaction_response_data(|d| d.content(reply))
+        })
+        .await
+        .unwrap();
+}
+
+async fn show_appeal_modal(ctx: &Context, component: &MessageComponentInteraction) {
+    let db = ctx.get_db().await;
+    let refusal = if !db.is_silenced(component.user.id.0).await.unwrap_or(false) {
+        Some("Et ole hiljennettynä.")
+    } else if let Ok(Some(_)) = db.get_pending_appeal(component.user.id.0).await {
+        Some("Aiempi valituksesi on vielä käsiteltävänä.")
+    } else {
+        None
+    };
+    if let Some(refusal) = refusal {
+        component
+            .create_interaction_response(&ctx.http, |r| {
+                r.kind(ChannelMessageWithSource)
+                    .interaction_response_data(|d| {
+                        d.content(refusal)
+                            .flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
+                    })
+            })
+            .await
+            .unwrap();
+        return;
+    }
+    component
+        .create_interaction_response(&ctx.http, |r| {
+            r.kind(Modal).interaction_response_data(|d| {
+                d.custom_id("appeal_modal")
+                    .title("Valitus hiljennyksestä")
+                    .components(|c| {
+                        c.create_action_row(|r| {
+                            r.create_input_text(|t| {
+                                t.custom_id("appeal_text")
+                                    .style(InputTextStyle::Paragraph)
+                                    .label("Miksi hiljennyksesi pitäisi purkaa?")
+                                    .min_length(10)
+                                    .max_length(1000)
+                                    .required(true)
+                            })
+                        })
+                    })
+            })
+        })
+        .await
+        .unwrap();
+}
+
+/// Posts the appeal to the moderation channel for the council to vote on. Returns the reply
+/// sent back to the member.
+async fn open_appeal(
+    ctx: &Context,
+    user: &User,
+    text: String,
+) -> Result<&'static str, anyhow::Error> {
+    let db = ctx.get_db().await;
+    if !db.is_silenced(user.id.0).await? {
+        return Ok("Et ole hiljennettynä.");
+    }
+    if db.get_pending_appeal(user.id.0).await?.is_some() {
+        return Ok("Aiempi valituksesi on vielä käsiteltävänä.");
+    }
+    let config = ctx.get_config().await;
+    let mods_online = get_online_mod_count(ctx).await;
+    let voting_message = ctx
+        .http
+        .get_channel(config.mod_channel_id)
+        .await?
+        .id()
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| e.title("Hiljennyksestä on valitettu!"))
+        })
+        .await?;
+    let voting = db.get_latest_voting_event_for_user(user.id.0).await?;
+    db.new_appeal(&NewAppeal {
+        user_id: user.id.0,
+        vote_message_id: voting_message.id.0,
+        council_vote_message_id: voting.map(|v| v.vote_message_id),
+        appeal_text: text,
+        created_at: chrono::Local::now().naive_local(),
+        votes_required: config
+            .council_actions
+            .threshold(CouncilAction::Silence)
+            .map_or(1, |t| t.weighted_votes_required(mods_online, 1.)),
+        moderators_online: mods_online as i32,
+    })
+    .await?;
+    info!("{} appealed their silence", user.tag());
+    update_appeal_message(ctx, voting_message.id.0).await?;
+    Ok("Valituksesi on lähetetty arvojäsenten käsiteltäväksi. Saat tiedon päätöksestä yksityisviestillä.")
+}
+
+async fn handle_appeal_vote(
+    ctx: &Context,
+    component: &MessageComponentInteraction,
+    vote_type: i32,
+) {
+    if !is_moderator(ctx, &component.user).await {
+        component
+            .create_interaction_response(&ctx.http, |r| {
+                r.interaction_response_data(|d| {
+                    d.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL);
+                    d.content("Vain arvojäsenet voivat äänestää valituksista")
+                })
+            })
+            .await
+            .unwrap();
+        return;
+    }
+    component
+        .create_interaction_response(&ctx.http, |r| r.kind(DeferredUpdateMessage))
+        .await
+        .unwrap();
+    let db = ctx.get_db().await;
+    let appeal = db.get_appeal(component.message.id.0).await.unwrap();
+    if appeal.resolution.is_some() {
+        return;
+    }
+    let votes = db
+        .toggle_appeal_vote(appeal.id, component.user.id.0, vote_type)
+        .await
+        .unwrap();
+    let count = |t: i32| votes.iter().filter(|v| v.vote_type == t).count() as i32;
+    if count(LIFT_VOTE) >= appeal.votes_required
+        && db.resolve_appeal(appeal.id, LIFTING).await.unwrap() > 0
+    {
+        if let Err(e) = lift_silence(ctx, &appeal, component.user.id.0).await {
+            error!("Unable to lift the silence of {}: {}", appeal.user_id, e);
+        }
+    } else if count(UPHOLD_VOTE) >= appeal.votes_required
+        && db.resolve_appeal(appeal.id, UPHELD).await.unwrap() > 0
+    {
+        uphold_silence(ctx, &appeal).await;
+    }
+    update_appeal_message(ctx, appeal.vote_message_id)
+        .await
+        .unwrap();
+}
+
+/// Lifts the silence of an appeal claimed as `LIFTING`. The appeal is reopened if the silence
+/// couldn't be lifted, so that the council can try again.
+async fn lift_silence(ctx: &Context, appeal: &Appeal, voter_id: u64) -> Result<(), anyhow::Error> {
+    let config = ctx.get_config().await;
+    let db = ctx.get_db().await;
+    let result = action::execute(
+        ctx,
+        Action::Unsilence {
+            user_id: appeal.user_id,
+            notice: format!(
+                "Valituksesi on hyväksytty ja hiljennyksesi on purettu. Tarkistathan sääntömme kanavalta <#{}>.",
+                config.rules_channel_id
+            ),
+        },
+        Some(voter_id),
+        ActionSource::CouncilVote,
+        Some(appeal.appeal_text.clone()),
+    )
+    .await
+    .into_result();
+    match result {
+        Ok(_) => {
+            db.set_appeal_resolution(appeal.id, Some(LIFTED)).await?;
+            Ok(())
+        }
+        Err(e) => {
+            db.set_appeal_resolution(appeal.id, None).await?;
+            Err(e)
+        }
+    }
+}
+
+async fn uphold_silence(ctx: &Context, appeal: &Appeal) {
+    let db = ctx.get_db().await;
+    let expiry = match db.get_silence(appeal.user_id).await.unwrap_or(None) {
+        Some(SilencedMember {
+            expires_at: Some(expires_at),
+            ..
+        }) => format!(" Hiljennyksesi päättyy <t:{}:f>.", expires_at.timestamp()),
+        _ => String::new(),
+    };
+    notify_member(
+        ctx,
+        appeal.user_id,
+        format!("Arvojäsenet ovat hylänneet valituksesi.{}", expiry),
+    )
+    .await;
+}
+
+async fn notify_member(ctx: &Context, user_id: u64, content: String) {
+    let dm = match UserId(user_id).create_dm_channel(&ctx.http).await {
+        Ok(channel) => channel.say(&ctx.http, content).await.map(|_| ()),
+        Err(e) => Err(e),
+    };
+    if dm.is_err() {
+        info!(
+            "Unable to send \"Appeal resolved\" notification to {}",
+            user_id
+        );
+    }
+}
+
+async fn update_appeal_message(ctx: &Context, vote_message_id: u64) -> Result<(), anyhow::Error> {
+    let config = ctx.get_config().await;
+    let db = ctx.get_db().await;
+    let appeal = db.get_appeal(vote_message_id).await?;
+    let appeal_votes = db.get_appeal_votes(appeal.id).await?;
+    let silence = db.get_silence(appeal.user_id).await?;
+    let context = match appeal.council_vote_message_id {
+        Some(voting_message_id) => {
+            let voting = db.get_voting_event(voting_message_id).await?;
+            let edits = db.get_voting_event_edits(voting_message_id).await?;
+            let votes = db.get_voting_event_votes(voting_message_id).await?;
+            let silence = CouncilAction::Silence.id();
+            let required = db
+                .get_voting_thresholds(voting_message_id)
+                .await?
+                .iter()
+                .find(|t| t.vote_type == silence)
+                .map_or(0, |t| t.votes_required);
+            let count = votes.iter().filter(|v| v.vote_type == silence).count();
+            let silence_votes = format!("Hiljennyksen puolesta {}/{}", count, required);
+            Some((voting, edits, silence_votes, filter_votes(silence, votes)))
+        }
+        None => None,
+    };
+    let user_tag = if let Ok(user) = UserId(appeal.user_id).to_user(&ctx.http).await {
+        user.tag()
+    } else {
+        String::from("[Poistettu käyttäjä]")
+    };
+    let mut message = ctx
+        .http
+        .get_message(config.mod_channel_id, vote_message_id)
+        .await?;
+    message
+        .edit(&ctx.http, |m| {
+            generate_appeal_message(
+                m,
+                appeal,
+                appeal_votes,
+                silence,
+                context,
+                user_tag,
+                config.guild_id,
+                config.mod_channel_id,
+            );
+            m
+        })
+        .await?;
+    Ok(())
+}
+
+fn appeal_voters(vote_type: i32, votes: &[AppealVote]) -> String {
+    let voters = votes
+        .iter()
+        .filter(|v| v.vote_type == vote_type)
+        .map(|v| format!("\n<@{}>", v.voter_user_id))
+        .collect::<String>();
+    if voters.is_empty() {
+        "-".to_string()
+    } else {
+        voters
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn generate_appeal_message(
+    message: &mut EditMessage,
+    appeal: Appeal,
+    votes: Vec<AppealVote>,
+    silence: Option<SilencedMember>,
+    context: Option<(CouncilVoting, Vec<SuspectMessageEdit>, String, String)>,
+    user_tag: String,
+    guild_id: u64,
+    mod_channel_id: u64,
+) {
+    message.embed(|e| {
+        e.color(serenity::utils::Color::ORANGE);
+        e.title("Hiljennyksestä on valitettu!");
+        e.field(
+            "Valittaja",
+            format!("<@{}>, {}", appeal.user_id, user_tag),
+            true,
+        );
+        e.field("Arvojäseniä paikalla", appeal.moderators_online, true);
+        if let Some(silence) = &silence {
+            if let Some(expires_at) = silence.expires_at {
+                e.field(
+                    "Hiljennys päättyy",
+                    format!("<t:{}:f>", expires_at.timestamp()),
+                    true,
+                );
+            }
+            if let Some(issuer_id) = silence.issuer_id {
+                e.field("Hiljentäjä", format!("<@{}>", issuer_id), true);
+            }
+        }
+        e.description(format!("Valitus:\n```\n{}```", appeal.appeal_text));
+        let count = |t: i32| votes.iter().filter(|v| v.vote_type == t).count();
+        e.field(
+            format!(
+                "Hiljennyksen pysyttämisen puolesta {}/{}",
+                count(UPHOLD_VOTE),
+                appeal.votes_required
+            ),
+            appeal_voters(UPHOLD_VOTE, &votes),
+            true,
+        );
+        e.field(
+            format!(
+                "Hiljennyksen purkamisen puolesta {}/{}",
+                count(LIFT_VOTE),
+                appeal.votes_required
+            ),
+            appeal_voters(LIFT_VOTE, &votes),
+            true,
+        );
+        if let Some(resolution) = &appeal.resolution {
+            let decision = match resolution.as_str() {
+                LIFTING => "Hiljennystä puretaan",
+                LIFTED => "Hiljennys purettu",
+                _ => "Hiljennys pysytetty",
+            };
+            e.field("Päätös", decision, false);
+        }
+        e.footer(|f| f.text(format!("Valitus lähetetty: {}", appeal.created_at)))
+    });
+    let mut voting_link = None;
+    let mut has_edits = false;
+    if let Some((voting, edits, silence_votes, silence_voters)) = context {
+        has_edits = !edits.is_empty();
+        voting_link = Some(format!(
+            "https://discord.com/channels/{}/{}/{}",
+            guild_id, mod_channel_id, voting.vote_message_id
+        ));
+        message.add_embed(|e| {
+            e.color(serenity::utils::Color::RED);
+            e.title("Alkuperäinen ilmoitus");
+            e.description(format!(
+                "Viestin sisältö:\n```\n{}```",
+                voting.suspect_message_content
+            ));
+            e.field(
+                "Viestin kanava",
+                format!("<#{}>", voting.suspect_message_channel_id),
+                true,
+            );
+            e.field(
+                "Ilmoituksen tehnyt",
+                format!("<@{}>", voting.reporter_id),
+                true,
+            );
+            e.field(silence_votes, silence_voters, true);
+            e.footer(|f| {
+                f.text(format!(
+                    "Viesti lähetetty: {}",
+                    voting.suspect_message_send_time
+                ))
+            })
+        });
+        // Discord allows ten embeds per message, so only the newest edits are shown. The rest
+        // are in the edit history.
+        for edit in edits.iter().rev().take(8).rev() {
+            if edit.new_content.is_empty() {
+                message.add_embed(|e| {
+                    e.title("Viesti on poistettu");
+                    e.footer(|f| f.text(format!("Poiston ajankohta: {}", edit.edit_time)))
+                });
+                break;
+            }
+            message.add_embed(|e| {
+                e.title("Viestiä on muokattu");
+                e.description(format!("Uusi sisältö:\n```\n{}```", edit.new_content));
+                e.footer(|f| f.text(format!("Muokkausajankohta: {}", edit.edit_time)))
+            });
+        }
+    }
+    let resolved = appeal.resolution.is_some();
+    message.components(|c| {
+        c.create_action_row(|r| {
+            r.create_button(|b| {
+                b.label("Pysytä hiljennys");
+                b.style(ButtonStyle::Danger);
+                b.disabled(resolved);
+                b.custom_id("appeal_uphold")
+            });
+            r.create_button(|b| {
+                b.label("Pura hiljennys");
+                b.style(ButtonStyle::Success);
+                b.disabled(resolved);
+                b.custom_id("appeal_lift")
+            });
+            if let Some(voting_link) = voting_link {
+                r.create_button(|b| {
+                    b.label("Näytä ilmoitus");
+                    b.style(ButtonStyle::Link);
+                    b.url(voting_link)
+                });
+            }
+            if has_edits {
+                r.create_button(|b| {
+                    b.label("Näytä muokkaushistoria");
+                    b.style(ButtonStyle::Secondary);
+                    b.custom_id("appeal_edit_history")
+                });
+            }
+            r
+        })
+    });
+}
@@ -0,0 +1,922 @@
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+
+use serenity::{
+    model::{
+        channel::{Attachment, Embed, GuildChannel, Message},
+        event::MessageUpdateEvent,
+        guild::Member,
+        user::User,
+    },
+    prelude::{Context, RwLock, TypeMap, TypeMapKey},
+};
+use unicode_general_category::{get_general_category, GeneralCategory};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{
+    config::Config,
+    extensions::*,
+    models::{BlacklistEntry, NewBlacklistHit},
+    moderation::{
+        action::{self, Action},
+        council::CouncilAction,
+        log_action, ActionKind, ActionSource,
+    },
+    voting::{self, Report, Suspect},
+};
+
+/// What is done to a message that matches a blacklist pattern
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlacklistAction {
+    /// Delete the message silently
+    Delete,
+    /// Delete the message and tell the author why
+    DeleteAndDm,
+    /// Delete the message and post it to the moderation channel
+    DeleteAndNotify,
+    /// Delete the message and time the author out
+    Timeout,
+    /// Leave the message be and open a council vote on it
+    Report,
+}
+
+impl BlacklistAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Delete => "delete",
+            Self::DeleteAndDm => "dm",
+            Self::DeleteAndNotify => "notify",
+            Self::Timeout => "timeout",
+            Self::Report => "report",
+        }
+    }
+}
+
+impl FromStr for BlacklistAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "delete" => Self::Delete,
+            "dm" => Self::DeleteAndDm,
+            "notify" => Self::DeleteAndNotify,
+            "timeout" => Self::Timeout,
+            "report" => Self::Report,
+            _ => anyhow::bail!("Unknown blacklist action `{}`", s),
+        })
+    }
+}
+
+/// How a pattern is matched against a message
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PatternFlags {
+    pub case_sensitive: bool,
+    /// Only match the pattern at word boundaries
+    pub whole_word: bool,
+    /// Match against the message with look-alike characters replaced, see `normalize`
+    pub normalize: bool,
+}
+
+impl PatternFlags {
+    pub fn of(entry: &BlacklistEntry) -> Self {
+        Self {
+            case_sensitive: entry.case_sensitive,
+            whole_word: entry.whole_word,
+            normalize: entry.normalize,
+        }
+    }
+}
+
+/// Turns a pattern and its flags into the regex that is actually compiled. The flags are
+/// applied inline so that patterns with different flags can share a single `RegexSet`.
+fn pattern_source(pattern: &str, flags: PatternFlags) -> String {
+    // Normalized patterns are matched against normalized text, so their literals have to be
+    // normalized too or a pattern written with look-alike characters could never match
+    let pattern = if flags.normalize {
+        normalize_pattern(pattern)
+    } else {
+        pattern.to_string()
+    };
+    let mut source = String::new();
+    if !flags.case_sensitive {
+        source.push_str("(?i)");
+    }
+    if flags.whole_word {
+        source.push_str(&format!(r"\b(?:{})\b", pattern));
+    } else {
+        source.push_str(&format!("(?:{})", pattern));
+    }
+    source
+}
+
+/// Compiles a single pattern, used to validate patterns before they are stored
+pub fn compile_pattern(pattern: &str, flags: PatternFlags) -> Result<regex::Regex, regex::Error> {
+    regex::Regex::new(&pattern_source(pattern, flags))
+}
+
+/// The letter a character commonly used to dodge the filter stands for
+fn look_alike(c: char) -> char {
+    match c {
+        '4' | '@' => 'a',
+        '8' => 'b',
+        '3' | '€' => 'e',
+        '6' => 'g',
+        '1' | '!' | '|' => 'i',
+        '0' => 'o',
+        '5' | '$' => 's',
+        '7' | '+' => 't',
+        '2' => 'z',
+        // Cyrillic and Greek letters that look like Latin ones
+        'а' | 'α' => 'a',
+        'А' | 'Α' => 'A',
+        'В' | 'Β' => 'B',
+        'с' | 'ϲ' => 'c',
+        'С' | 'Ϲ' => 'C',
+        'е' | 'ε' => 'e',
+        'Е' | 'Ε' => 'E',
+        'һ' => 'h',
+        'Н' | 'Η' => 'H',
+        'і' | 'ι' => 'i',
+        'І' | 'Ι' => 'I',
+        'ј' => 'j',
+        'Ј' => 'J',
+        'к' | 'κ' => 'k',
+        'К' | 'Κ' => 'K',
+        'М' | 'Μ' => 'M',
+        'Ν' => 'N',
+        'о' | 'ο' => 'o',
+        'О' | 'Ο' => 'O',
+        'р' | 'ρ' => 'p',
+        'Р' | 'Ρ' => 'P',
+        'ѕ' => 's',
+        'Ѕ' => 'S',
+        'Т' | 'Τ' => 'T',
+        'υ' => 'u',
+        'ν' => 'v',
+        'х' | 'χ' => 'x',
+        'Х' | 'Χ' => 'X',
+        'у' => 'y',
+        'У' | 'Υ' => 'Y',
+        'Ζ' => 'Z',
+        _ => c,
+    }
+}
+
+fn normalize_chars(chars: impl Iterator<Item = char>) -> impl Iterator<Item = char> {
+    chars
+        .nfkd()
+        .filter(|c| {
+            !matches!(
+                get_general_category(*c),
+                GeneralCategory::NonspacingMark | GeneralCategory::Format
+            )
+        })
+        .map(look_alike)
+        .nfc()
+}
+
+/// Brings the text to a form where characters commonly used to dodge the filter are replaced
+/// with the letters they stand for, so that for example "p4sk4", "ｐａｓｋａ" and "påskå" all
+/// match a pattern written as "paska". Compatibility forms such as fullwidth letters are
+/// replaced by NFKC, and accents and invisible characters like zero-width joiners are dropped.
+pub fn normalize(text: &str) -> String {
+    normalize_chars(text.chars()).collect()
+}
+
+/// Normalizes the literal characters of a pattern like `normalize` does the text. Escapes,
+/// character classes and repetitions are kept as written, apart from escaped look-alikes such
+/// as `\$`, which become the letter they stand for.
+fn normalize_pattern(pattern: &str) -> String {
+    let mut normalized = String::new();
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let Some(escaped) = chars.next() else {
+                    normalized.push(c);
+                    break;
+                };
+                let letter = look_alike(escaped);
+                if !escaped.is_alphanumeric() && letter.is_alphanumeric() {
+                    normalized.push(letter);
+                    continue;
+                }
+                normalized.push(c);
+                normalized.push(escaped);
+                // The digits of a character code without braces, like `\x41`
+                let digits = match escaped {
+                    'x' => 2,
+                    'u' => 4,
+                    'U' => 8,
+                    _ => 0,
+                };
+                if chars.peek() != Some(&'{') {
+                    normalized.extend(chars.by_ref().take(digits));
+                }
+            }
+            '[' | '{' => {
+                let end = if c == '[' { ']' } else { '}' };
+                normalized.push(c);
+                let mut escaped = false;
+                for c in chars.by_ref() {
+                    normalized.push(c);
+                    match c {
+                        _ if escaped => escaped = false,
+                        '\\' => escaped = true,
+                        _ if c == end => break,
+                        _ => {}
+                    }
+                }
+            }
+            '.' | '^' | '$' | '*' | '+' | '?' | '(' | ')' | '|' => normalized.push(c),
+            _ => normalized.extend(normalize_chars(std::iter::once(c))),
+        }
+    }
+    normalized
+}
+
+/// A pattern from the `BlacklistEntries` table
+pub struct BlacklistPattern {
+    pub id: i32,
+    pub pattern: String,
+    pub reason: Option<String>,
+    pub action: BlacklistAction,
+    pub timeout_minutes: Option<i32>,
+    pub flags: PatternFlags,
+}
+
+/// The enabled blacklist patterns, compiled once whenever the table changes. Patterns are
+/// split into two sets depending on whether they match the raw or the normalized text.
+pub struct Blacklist {
+    patterns: Vec<BlacklistPattern>,
+    raw: regex::RegexSet,
+    raw_indices: Vec<usize>,
+    normalized: regex::RegexSet,
+    normalized_indices: Vec<usize>,
+}
+
+impl TypeMapKey for Blacklist {
+    type Value = Arc<Blacklist>;
+}
+
+impl Blacklist {
+    pub fn compile(entries: Vec<BlacklistEntry>) -> Self {
+        let mut patterns = Vec::new();
+        let mut raw = (Vec::new(), Vec::new());
+        let mut normalized = (Vec::new(), Vec::new());
+        for entry in entries {
+            let flags = PatternFlags::of(&entry);
+            // Compile each pattern on its own first, so that a single invalid one is
+            // skipped instead of taking the whole set down
+            if let Err(e) = compile_pattern(&entry.pattern, flags) {
+                warn!("Skipping invalid blacklist entry #{}: {}", entry.id, e);
+                continue;
+            }
+            let action = entry.action.parse().unwrap_or_else(|e| {
+                warn!("Blacklist entry #{}: {}, deleting instead", entry.id, e);
+                BlacklistAction::Delete
+            });
+
+            let (sources, indices) = if flags.normalize {
+                &mut normalized
+            } else {
+                &mut raw
+            };
+            sources.push(pattern_source(&entry.pattern, flags));
+            indices.push(patterns.len());
+
+            patterns.push(BlacklistPattern {
+                id: entry.id,
+                pattern: entry.pattern,
+                reason: entry.reason,
+                action,
+                timeout_minutes: entry.timeout_minutes,
+                flags,
+            });
+        }
+
+        let build = |sources: &[String]| {
+            regex::RegexSet::new(sources).unwrap_or_else(|e| {
+                error!("Unable to compile the blacklist: {}", e);
+                regex::RegexSet::empty()
+            })
+        };
+        Self {
+            raw: build(&raw.0),
+            raw_indices: raw.1,
+            normalized: build(&normalized.0),
+            normalized_indices: normalized.1,
+            patterns,
+        }
+    }
+
+    pub fn is_match(&self, text: &str) -> bool {
+        self.raw.is_match(text)
+            || (!self.normalized_indices.is_empty() && self.normalized.is_match(&normalize(text)))
+    }
+
+    /// The patterns matching the text, in the order they were added
+    pub fn matches(&self, text: &str) -> impl Iterator<Item = &BlacklistPattern> {
+        let mut indices = self
+            .raw
+            .matches(text)
+            .iter()
+            .map(|i| self.raw_indices[i])
+            .collect::<Vec<_>>();
+        if !self.normalized_indices.is_empty() {
+            indices.extend(
+                self.normalized
+                    .matches(&normalize(text))
+                    .iter()
+                    .map(|i| self.normalized_indices[i]),
+            );
+        }
+        indices.sort_unstable();
+        indices.into_iter().map(move |i| &self.patterns[i])
+    }
+}
+
+/// Compile the enabled entries from the database and swap them into the `TypeMap`
+pub async fn reload(data: &RwLock<TypeMap>) -> Result<(), anyhow::Error> {
+    let entries = data.get_db().await.get_blacklist_entries().await?;
+    let blacklist = Blacklist::compile(entries);
+    info!("Loaded {} blacklist patterns", blacklist.patterns.len());
+    data.write().await.insert::<Blacklist>(Arc::new(blacklist));
+    Ok(())
+}
+
+/// Where the text that matched a pattern came from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScanSource {
+    Content,
+    Embed,
+    Attachment,
+    ThreadTitle,
+    Username,
+    Nickname,
+}
+
+impl ScanSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Content => "content",
+            Self::Embed => "embed",
+            Self::Attachment => "attachment",
+            Self::ThreadTitle => "thread",
+            Self::Username => "username",
+            Self::Nickname => "nickname",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Self::Content => "Viestin sisältö",
+            Self::Embed => "Viestin upote",
+            Self::Attachment => "Liitteen nimi",
+            Self::ThreadTitle => "Ketjun nimi",
+            Self::Username => "Käyttäjänimi",
+            Self::Nickname => "Nimimerkki",
+        }
+    }
+
+    /// The action configured for this source, if it overrides the action of the pattern
+    fn configured_action(&self, config: &Config) -> Option<BlacklistAction> {
+        match self {
+            Self::Content => None,
+            Self::Embed => config.blacklist_embed_action,
+            Self::Attachment => config.blacklist_attachment_action,
+            Self::ThreadTitle => config.blacklist_thread_action,
+            Self::Username | Self::Nickname => config.blacklist_name_action,
+        }
+    }
+}
+
+/// The nickname given to members whose name matches the blacklist
+pub const NICKNAME_PLACEHOLDER: &str = "Nimimerkki poistettu";
+
+/// The thing a blacklist hit is acted on
+enum Target<'a> {
+    Message(&'a Message),
+    Thread(&'a GuildChannel),
+    Member(&'a Member),
+}
+
+impl Target<'_> {
+    /// Threads don't tell who created them, so there's no one to act on
+    fn user(&self) -> Option<&User> {
+        match self {
+            Self::Message(msg) => Some(&msg.author),
+            Self::Thread(_) => None,
+            Self::Member(member) => Some(&member.user),
+        }
+    }
+
+    fn channel_id(&self) -> Option<u64> {
+        match self {
+            Self::Message(msg) => Some(msg.channel_id.0),
+            Self::Thread(thread) => Some(thread.id.0),
+            Self::Member(_) => None,
+        }
+    }
+
+    fn message_id(&self) -> Option<u64> {
+        match self {
+            Self::Message(msg) => Some(msg.id.0),
+            _ => None,
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Self::Message(_) => "Estolistalla oleva viesti poistettu",
+            Self::Thread(_) => "Estolistalla oleva ketju poistettu",
+            Self::Member(_) => "Estolistalla oleva nimi piilotettu",
+        }
+    }
+
+    /// Gets rid of the offending content: the message or thread is deleted and a member's
+    /// name is hidden behind a placeholder nickname
+    async fn remove(&self, ctx: &Context) {
+        let result = match self {
+            Self::Message(msg) => msg.delete(&ctx.http).await,
+            Self::Thread(thread) => thread.delete(&ctx.http).await.map(|_| ()),
+            Self::Member(member) => member
+                .edit(&ctx.http, |m| m.nickname(NICKNAME_PLACEHOLDER))
+                .await
+                .map(|_| ()),
+        };
+        if let Err(e) = result {
+            error!("Unable to remove blacklisted content: {}", e);
+        }
+    }
+
+    async fn dm(&self, ctx: &Context, reason: Option<&String>) {
+        let Some(user) = self.user() else {
+            return;
+        };
+        let explanation = match self {
+            Self::Message(msg) => format!(
+                "Viestisi kanavalla <#{}> poistettiin, koska se sisälsi kiellettyä sisältöä.",
+                msg.channel_id.0
+            ),
+            Self::Thread(thread) => format!(
+                "Ketjusi {} poistettiin, koska sen nimi sisälsi kiellettyä sisältöä.",
+                thread.name
+            ),
+            Self::Member(_) => {
+                "Nimimerkkisi vaihdettiin, koska nimesi sisälsi kiellettyä sisältöä.".to_string()
+            }
+        };
+        let reason = reason.map(|r| format!("\nSyy: {}", r)).unwrap_or_default();
+        if user
+            .dm(&ctx.http, |m| {
+                m.content(format!("{}{}", explanation, reason))
+            })
+            .await
+            .is_err()
+        {
+            info!("Unable to send blacklist notification to {}", user.id.0);
+        }
+    }
+
+    /// Writes the action to the moderation log. Threads have no known author, so there is no
+    /// one to log them for.
+    async fn log(
+        &self,
+        ctx: &Context,
+        kind: ActionKind,
+        pattern: &BlacklistPattern,
+        expires_at: Option<chrono::NaiveDateTime>,
+    ) {
+        let Some(user) = self.user() else {
+            return;
+        };
+        log_action(
+            ctx,
+            user.id.0,
+            Some(ctx.cache.current_user_id().0),
+            kind,
+            ActionSource::Blacklist,
+            Some(format!("Estolistan kohta #{}", pattern.id)),
+            expires_at,
+        )
+        .await;
+    }
+
+    async fn timeout(&self, ctx: &Context, pattern: &BlacklistPattern) {
+        let Some(user) = self.user() else {
+            return;
+        };
+        let minutes = pattern.timeout_minutes.unwrap_or(10) as i64;
+        action::execute(
+            ctx,
+            Action::Timeout {
+                user_id: user.id.0,
+                until: chrono::Utc::now() + chrono::Duration::minutes(minutes),
+                notice: None,
+            },
+            Some(ctx.cache.current_user_id().0),
+            ActionSource::Blacklist,
+            Some(format!("Estolistan kohta #{}", pattern.id)),
+        )
+        .await;
+    }
+}
+
+async fn notify_moderators(
+    ctx: &Context,
+    target: &Target<'_>,
+    source: ScanSource,
+    pattern: &BlacklistPattern,
+    text: &str,
+) {
+    let config = ctx.get_config().await;
+    let text = text.chars().take(1000).collect::<String>();
+    if let Err(e) = serenity::model::id::ChannelId(config.mod_channel_id)
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.color(serenity::utils::Color::ORANGE);
+                e.title(target.title());
+                if let Some(user) = target.user() {
+                    e.field(
+                        "Käyttäjä",
+                        format!("<@{}>, {}", user.id.0, user.tag()),
+                        true,
+                    );
+                }
+                if let Some(channel_id) = target.channel_id() {
+                    e.field("Kanava", format!("<#{}>", channel_id), true);
+                }
+                e.field(
+                    "Estolistan kohta",
+                    format!("#{} `{}`", pattern.id, pattern.pattern),
+                    true,
+                );
+                e.description(format!("{}:\n```\n{}```", source.description(), text))
+            })
+        })
+        .await
+    {
+        error!(
+            "Unable to post a blacklist hit to the moderation channel: {}",
+            e
+        );
+    }
+}
+
+/// The actions offered on the council votes opened for blacklist hits
+const REPORT_ACTIONS: [CouncilAction; 5] = [
+    CouncilAction::Delete,
+    CouncilAction::Silence,
+    CouncilAction::Timeout,
+    CouncilAction::LockThread,
+    CouncilAction::Kick,
+];
+
+/// Logs a hit and applies the action of the pattern, or the action configured for the source
+async fn apply(
+    ctx: &Context,
+    target: Target<'_>,
+    source: ScanSource,
+    pattern: &BlacklistPattern,
+    text: &str,
+) {
+    let action = source
+        .configured_action(&*ctx.get_config().await)
+        .unwrap_or(pattern.action);
+    info!(
+        "Blacklist entry #{} matched the {} of {:?}, action: {}",
+        pattern.id,
+        source.as_str(),
+        target.user().map(|u| u.id.0),
+        action.as_str()
+    );
+
+    let hit = NewBlacklistHit {
+        entry_id: pattern.id,
+        user_id: target.user().map(|u| u.id.0),
+        channel_id: target.channel_id(),
+        message_id: target.message_id(),
+        source: source.as_str().to_string(),
+        content: text.to_string(),
+        action: action.as_str().to_string(),
+        hit_time: chrono::Local::now().naive_local(),
+    };
+    if let Err(e) = ctx.get_db().await.log_blacklist_hit(&hit).await {
+        error!("Unable to log blacklist hit: {}", e);
+    }
+
+    if action != BlacklistAction::Report {
+        let kind = match target {
+            Target::Member(_) => ActionKind::HideName,
+            _ => ActionKind::Delete,
+        };
+        target.log(ctx, kind, pattern, None).await;
+    }
+
+    match (action, &target) {
+        (BlacklistAction::Report, Target::Message(msg)) => {
+            // The bot is the reporter, so blocking the reporter is left out of the vote
+            voting::report_with_actions(
+                ctx,
+                Suspect::from(*msg),
+                Report {
+                    reporter_id: ctx.cache.current_user_id().0,
+                    category: None,
+                    reason: pattern.reason.clone(),
+                },
+                Some(&REPORT_ACTIONS),
+            )
+            .await;
+        }
+        // Only messages can be voted on
+        (BlacklistAction::Report, _) => {
+            notify_moderators(ctx, &target, source, pattern, text).await;
+        }
+        (BlacklistAction::Delete, _) => target.remove(ctx).await,
+        (BlacklistAction::DeleteAndDm, _) => {
+            target.remove(ctx).await;
+            target.dm(ctx, pattern.reason.as_ref()).await;
+        }
+        (BlacklistAction::DeleteAndNotify, _) => {
+            target.remove(ctx).await;
+            notify_moderators(ctx, &target, source, pattern, text).await;
+        }
+        (BlacklistAction::Timeout, _) => {
+            target.remove(ctx).await;
+            target.timeout(ctx, pattern).await;
+        }
+    }
+}
+
+pub fn embed_text(embed: &Embed) -> String {
+    let mut parts = vec![
+        embed.title.clone(),
+        embed.description.clone(),
+        embed.url.clone(),
+        embed.author.as_ref().map(|a| a.name.clone()),
+        embed.footer.as_ref().map(|f| f.text.clone()),
+        embed.provider.as_ref().and_then(|p| p.name.clone()),
+    ];
+    for field in &embed.fields {
+        parts.push(Some(field.name.clone()));
+        parts.push(Some(field.value.clone()));
+    }
+    parts.into_iter().flatten().collect::<Vec<_>>().join("\n")
+}
+
+/// The texts of a message that are checked against the blacklist
+fn message_texts(
+    content: Option<&String>,
+    embeds: &[Embed],
+    attachments: &[Attachment],
+) -> Vec<(ScanSource, String)> {
+    let mut texts = Vec::new();
+    if let Some(content) = content {
+        texts.push((ScanSource::Content, content.clone()));
+    }
+    for embed in embeds {
+        texts.push((ScanSource::Embed, embed_text(embed)));
+    }
+    for attachment in attachments {
+        texts.push((ScanSource::Attachment, attachment.filename.clone()));
+    }
+    texts
+}
+
+impl Blacklist {
+    /// The first text that matches a pattern, along with the first pattern it matches
+    fn first_match<'a>(
+        &'a self,
+        texts: &'a [(ScanSource, String)],
+    ) -> Option<(ScanSource, &'a BlacklistPattern, &'a str)> {
+        texts.iter().find_map(|(source, text)| {
+            self.matches(text)
+                .next()
+                .map(|pattern| (*source, pattern, text.as_str()))
+        })
+    }
+
+    /// Whether the changed parts of an edited message match, so that the full message only
+    /// has to be fetched when something is going to be done to it
+    pub fn is_match_update(&self, event: &MessageUpdateEvent) -> bool {
+        let texts = message_texts(
+            event.content.as_ref(),
+            event.embeds.as_deref().unwrap_or_default(),
+            event.attachments.as_deref().unwrap_or_default(),
+        );
+        texts.iter().any(|(_, text)| self.is_match(text))
+    }
+}
+
+/// Checks a message, its embeds and its attachments against the blacklist and applies the
+/// action of the first matching pattern. Returns `true` if the message matched.
+pub async fn enforce(ctx: &Context, msg: &Message) -> bool {
+    // The notices of the bot in the moderation channel quote the offending text, and DMs and
+    // other guilds are none of the blacklist's business
+    let config = ctx.get_config().await;
+    if msg.author.bot
+        || msg.guild_id.map(|g| g.0) != Some(config.guild_id)
+        || msg.channel_id.0 == config.mod_channel_id
+    {
+        return false;
+    }
+    let blacklist = ctx.get_blacklist().await;
+    let texts = message_texts(Some(&msg.content), &msg.embeds, &msg.attachments);
+    let Some((source, pattern, text)) = blacklist.first_match(&texts) else {
+        return false;
+    };
+    apply(ctx, Target::Message(msg), source, pattern, text).await;
+    true
+}
+
+/// The last checked title of each thread, so that updates that leave the title as it was
+/// aren't checked again
+struct ThreadTitles;
+
+impl TypeMapKey for ThreadTitles {
+    type Value = HashMap<u64, String>;
+}
+
+/// Checks the title of a new or renamed thread against the blacklist
+pub async fn enforce_thread(ctx: &Context, thread: &GuildChannel) -> bool {
+    // The model has no owner for threads, but a thread started from a message shares its id
+    let started_by_bot = thread
+        .parent_id
+        .and_then(|parent| ctx.cache.message(parent, thread.id.0))
+        .is_some_and(|m| m.author.bot);
+    let config = ctx.get_config().await;
+    if started_by_bot
+        || thread.guild_id.0 != config.guild_id
+        || thread.parent_id.map(|p| p.0) == Some(config.mod_channel_id)
+    {
+        return false;
+    }
+    {
+        let mut data = ctx.data.write().await;
+        let titles = data.entry::<ThreadTitles>().or_insert_with(HashMap::new);
+        if titles.get(&thread.id.0) == Some(&thread.name) {
+            return false;
+        }
+        titles.insert(thread.id.0, thread.name.clone());
+    }
+    let blacklist = ctx.get_blacklist().await;
+    let texts = [(ScanSource::ThreadTitle, thread.name.clone())];
+    let Some((source, pattern, text)) = blacklist.first_match(&texts) else {
+        return false;
+    };
+    apply(ctx, Target::Thread(thread), source, pattern, text).await;
+    true
+}
+
+/// Checks the username and nickname of a member against the blacklist
+pub async fn enforce_member(ctx: &Context, member: &Member) -> bool {
+    // The placeholder hides a blacklisted username, so the member has been dealt with already
+    if member.nick.as_deref() == Some(NICKNAME_PLACEHOLDER) {
+        return false;
+    }
+    let blacklist = ctx.get_blacklist().await;
+    let mut texts = vec![(ScanSource::Username, member.user.name.clone())];
+    if let Some(nick) = &member.nick {
+        texts.push((ScanSource::Nickname, nick.clone()));
+    }
+    let Some((source, pattern, text)) = blacklist.first_match(&texts) else {
+        return false;
+    };
+    apply(ctx, Target::Member(member), source, pattern, text).await;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(id: i32, pattern: &str, flags: PatternFlags) -> BlacklistEntry {
+        BlacklistEntry {
+            id,
+            pattern: pattern.to_string(),
+            added_by: 0,
+            added_at: chrono::Local::now().naive_local(),
+            reason: None,
+            enabled: true,
+            action: String::from("delete"),
+            timeout_minutes: None,
+            case_sensitive: flags.case_sensitive,
+            whole_word: flags.whole_word,
+            normalize: flags.normalize,
+        }
+    }
+
+    fn ids(blacklist: &Blacklist, text: &str) -> Vec<i32> {
+        blacklist.matches(text).map(|p| p.id).collect()
+    }
+
+    #[test]
+    fn ignores_case_by_default() {
+        let blacklist = Blacklist::compile(vec![
+            entry(1, "kissa", PatternFlags::default()),
+            entry(
+                2,
+                "Koira",
+                PatternFlags {
+                    case_sensitive: true,
+                    ..Default::default()
+                },
+            ),
+        ]);
+        assert_eq!(ids(&blacklist, "KISSA"), vec![1]);
+        assert_eq!(ids(&blacklist, "koira"), Vec::<i32>::new());
+        assert_eq!(ids(&blacklist, "Koira"), vec![2]);
+    }
+
+    #[test]
+    fn whole_word_matches_at_word_boundaries() {
+        let whole_word = PatternFlags {
+            whole_word: true,
+            ..Default::default()
+        };
+        let blacklist = Blacklist::compile(vec![entry(1, "ase", whole_word)]);
+        assert!(blacklist.is_match("ase kädessä"));
+        assert!(blacklist.is_match("mikä ase?"));
+        assert!(!blacklist.is_match("asema"));
+        assert!(!blacklist.is_match("kaasu"));
+    }
+
+    #[test]
+    fn normalizes_look_alike_characters() {
+        assert_eq!(normalize("p4sk4"), "paska");
+        assert_eq!(normalize("$1@7"), "siat");
+        let blacklist = Blacklist::compile(vec![entry(
+            1,
+            "paska",
+            PatternFlags {
+                normalize: true,
+                ..Default::default()
+            },
+        )]);
+        assert!(blacklist.is_match("p4sk4"));
+        assert!(blacklist.is_match("P4SKA"));
+    }
+
+    #[test]
+    fn normalizes_unicode_look_alikes() {
+        assert_eq!(normalize("ｐａｓｋａ"), "paska");
+        assert_eq!(normalize("pàšká"), "paska");
+        assert_eq!(normalize("pa\u{200d}s\u{200b}ka"), "paska");
+        // Cyrillic "р", "а", "ѕ" and "к"
+        assert_eq!(normalize("раѕка"), "paska");
+        assert_eq!(normalize("ＰＡＳＫＡ"), "PASKA");
+    }
+
+    #[test]
+    fn normalizes_pattern_literals_only() {
+        assert_eq!(normalize_pattern("p4sk4"), "paska");
+        assert_eq!(normalize_pattern(r"p4\$k4"), "paska");
+        assert_eq!(normalize_pattern("pä+s"), "pa+s");
+        assert_eq!(
+            normalize_pattern(r"[0-9]{2,3}\d\x41\.$"),
+            r"[0-9]{2,3}\d\x41\.$"
+        );
+        assert_eq!(normalize_pattern(r"(?i)k1ssa|k0ira"), "(?i)kissa|koira");
+
+        let blacklist = Blacklist::compile(vec![entry(
+            1,
+            r"p4\$k4",
+            PatternFlags {
+                normalize: true,
+                ..Default::default()
+            },
+        )]);
+        assert!(blacklist.is_match("paska"));
+        assert!(blacklist.is_match("p@$k@"));
+    }
+
+    #[test]
+    fn matches_raw_and_normalized_patterns_in_order() {
+        let normalized = PatternFlags {
+            normalize: true,
+            ..Default::default()
+        };
+        let blacklist = Blacklist::compile(vec![
+            entry(1, "paska", normalized),
+            entry(2, "p4", PatternFlags::default()),
+            entry(3, "sk4", normalized),
+            entry(4, "kissa", PatternFlags::default()),
+        ]);
+        // "sk4" is normalized to "ska" like the text, so it matches the normalized "paska"
+        assert_eq!(ids(&blacklist, "p4sk4"), vec![1, 2, 3]);
+        assert_eq!(ids(&blacklist, "kissa"), vec![4]);
+        assert_eq!(ids(&blacklist, "koira"), Vec::<i32>::new());
+    }
+
+    #[test]
+    fn skips_invalid_patterns() {
+        let blacklist = Blacklist::compile(vec![
+            entry(1, "kissa", PatternFlags::default()),
+            entry(2, "(koira", PatternFlags::default()),
+            entry(3, "hevo[", PatternFlags::default()),
+            entry(4, "lehmä", PatternFlags::default()),
+        ]);
+        assert_eq!(ids(&blacklist, "kissa ja lehmä"), vec![1, 4]);
+        assert!(!blacklist.is_match("(koira"));
+    }
+}
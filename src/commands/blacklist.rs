@@ -0,0 +1,241 @@
+use serenity::{
+    model::{
+        interactions::InteractionApplicationCommandCallbackDataFlags,
+        prelude::application_command::ApplicationCommandInteraction,
+    },
+    prelude::Context,
+};
+
+use crate::{
+    blacklist::{self, compile_pattern, BlacklistAction, PatternFlags},
+    extensions::*,
+    voting::is_moderator,
+};
+
+async fn respond(ctx: &Context, interaction: &ApplicationCommandInteraction, content: String) {
+    interaction
+        .create_interaction_response(&ctx.http, |r| {
+            r.interaction_response_data(|d| {
+                d.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL);
+                d.content(content)
+            })
+        })
+        .await
+        .unwrap();
+}
+
+/// Short markers for the flags of a pattern in listings
+fn describe_flags(flags: PatternFlags) -> String {
+    let mut markers = Vec::new();
+    if flags.case_sensitive {
+        markers.push("Aa");
+    }
+    if flags.whole_word {
+        markers.push("sana");
+    }
+    if flags.normalize {
+        markers.push("4→a");
+    }
+    if markers.is_empty() {
+        String::new()
+    } else {
+        format!(" [{}]", markers.join(", "))
+    }
+}
+
+/// Truncate a pattern so that a listing of them fits in a single message
+fn shorten(text: &str, max: usize) -> String {
+    if text.chars().count() > max {
+        format!("{}…", text.chars().take(max).collect::<String>())
+    } else {
+        text.to_string()
+    }
+}
+
+pub async fn handle_interaction(ctx: &Context, interaction: ApplicationCommandInteraction) {
+    if !is_moderator(ctx, &interaction.user).await {
+        respond(
+            ctx,
+            &interaction,
+            "Vain arvojäsenet voivat muokata estolistaa".to_string(),
+        )
+        .await;
+        return;
+    }
+
+    let db = ctx.get_db().await;
+    let option = interaction
+        .data
+        .options
+        .first()
+        .expect("Blacklist subcommand missing");
+    let sub_options = option.options.clone();
+
+    match option.name.as_str() {
+        "add" => {
+            let pattern = sub_options
+                .by_name("pattern")
+                .expect("Missing pattern option")
+                .to_string()
+                .expect("Invalid pattern option");
+            let reason = sub_options.by_name("reason").and_then(|x| x.to_string());
+            let action = sub_options
+                .by_name("action")
+                .and_then(|x| x.to_string())
+                .map_or(Ok(BlacklistAction::Delete), |x| x.parse())
+                .expect("Invalid action option");
+            let timeout = sub_options
+                .by_name("timeout")
+                .and_then(|x| x.to_i64())
+                .map(|x| x as i32);
+            let flag = |name| {
+                sub_options
+                    .by_name(name)
+                    .and_then(|x| x.to_bool())
+                    .unwrap_or(false)
+            };
+            let flags = PatternFlags {
+                case_sensitive: flag("case_sensitive"),
+                whole_word: flag("whole_word"),
+                normalize: flag("normalize"),
+            };
+
+            if matches!(timeout, Some(t) if t < 1) {
+                respond(
+                    ctx,
+                    &interaction,
+                    "Jäähyn pituuden on oltava positiivinen".to_string(),
+                )
+                .await;
+                return;
+            }
+
+            if let Err(e) = compile_pattern(&pattern, flags) {
+                respond(
+                    ctx,
+                    &interaction,
+                    format!("Virheellinen säännöllinen lauseke:\n```\n{}```", e),
+                )
+                .await;
+                return;
+            }
+
+            let id = db
+                .add_blacklist_entry(
+                    &pattern,
+                    interaction.user.id.0,
+                    reason,
+                    action.as_str(),
+                    timeout,
+                    flags,
+                )
+                .await
+                .unwrap();
+            blacklist::reload(&ctx.data).await.unwrap();
+            info!(
+                "User {} added blacklist entry #{}: {}",
+                interaction.user.id.0, id, pattern
+            );
+            respond(
+                ctx,
+                &interaction,
+                format!("Lisätty estolistaan tunnisteella #{}", id),
+            )
+            .await;
+        }
+        "remove" => {
+            let id = sub_options
+                .by_name("id")
+                .expect("Missing id option")
+                .to_i64()
+                .expect("Invalid id option");
+
+            let content = if db.remove_blacklist_entry(id as i32).await.unwrap() == 0 {
+                format!("Estolistalla ei ole kohtaa #{}", id)
+            } else {
+                blacklist::reload(&ctx.data).await.unwrap();
+                info!(
+                    "User {} removed blacklist entry #{}",
+                    interaction.user.id.0, id
+                );
+                format!("Kohta #{} poistettu estolistalta", id)
+            };
+            respond(ctx, &interaction, content).await;
+        }
+        "list" => {
+            let entries = db.get_blacklist_entries().await.unwrap();
+            let lines = entries
+                .iter()
+                .map(|e| {
+                    format!(
+                        "**#{}** `{}`{} ({}) – <@{}>, {}{}",
+                        e.id,
+                        shorten(&e.pattern, 60),
+                        describe_flags(PatternFlags::of(e)),
+                        e.action,
+                        e.added_by,
+                        e.added_at.format("%d.%m.%Y"),
+                        e.reason
+                            .as_ref()
+                            .map(|r| format!(": {}", shorten(r, 60)))
+                            .unwrap_or_default()
+                    )
+                })
+                .collect::<Vec<_>>();
+
+            let mut description = String::new();
+            for (shown, line) in lines.iter().enumerate() {
+                if description.len() + line.len() > 4000 {
+                    description.push_str(&format!("\n…ja {} muuta", lines.len() - shown));
+                    break;
+                }
+                description.push_str(line);
+                description.push('\n');
+            }
+            if description.is_empty() {
+                description = "Estolista on tyhjä".to_string();
+            }
+
+            interaction
+                .create_interaction_response(&ctx.http, |r| {
+                    r.interaction_response_data(|d| {
+                        d.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL);
+                        d.embed(|e| {
+                            e.title("Estolista");
+                            e.description(description)
+                        })
+                    })
+                })
+                .await
+                .unwrap();
+        }
+        "test" => {
+            let text = sub_options
+                .by_name("text")
+                .expect("Missing text option")
+                .to_string()
+                .expect("Invalid text option");
+
+            let blacklist = ctx.get_blacklist().await;
+            let matches = blacklist
+                .matches(&text)
+                .map(|p| {
+                    format!(
+                        "**#{}** `{}`{} ({})",
+                        p.id,
+                        shorten(&p.pattern, 60),
+                        describe_flags(p.flags),
+                        p.action.as_str()
+                    )
+                })
+                .collect::<Vec<_>>();
+            let content = if matches.is_empty() {
+                "Mikään estolistan kohta ei vastaa tekstiä".to_string()
+            } else {
+                format!("Tekstiä vastaavat kohdat:\n{}", matches.join("\n"))
+            };
+            respond(ctx, &interaction, content).await;
+        }
+        _ => panic!("Unknown command {}", interaction.data.name),
+    }
+}
@@ -0,0 +1,50 @@
+use serenity::{
+    model::{
+        interactions::InteractionApplicationCommandCallbackDataFlags,
+        prelude::application_command::ApplicationCommandInteraction,
+    },
+    prelude::Context,
+};
+
+use crate::{extensions::*, moderation::council_stats::CouncilStats, voting::is_moderator};
+
+pub async fn handle_interaction(ctx: &Context, interaction: ApplicationCommandInteraction) {
+    if !is_moderator(ctx, &interaction.user).await {
+        interaction
+            .create_interaction_response(&ctx.http, |r| {
+                r.interaction_response_data(|d| {
+                    d.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL);
+                    d.content("Vain arvojäsenet voivat tarkastella neuvoston tilastoja")
+                })
+            })
+            .await
+            .unwrap();
+        return;
+    }
+
+    let config = ctx.get_config().await;
+    let days = interaction
+        .data
+        .options
+        .by_name("days")
+        .and_then(|x| x.to_i64())
+        .unwrap_or(7);
+    let db = ctx.get_db().await;
+    let stats = CouncilStats::collect(&db, chrono::Utc::now() - chrono::Duration::days(days))
+        .await
+        .unwrap();
+
+    interaction
+        .create_interaction_response(&ctx.http, |r| {
+            r.interaction_response_data(|d| {
+                d.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL);
+                d.embed(|e| {
+                    e.title(format!("Neuvoston tilastot, {} päivää", days));
+                    stats.write_embed(e, config.guild_id, config.mod_channel_id);
+                    e
+                })
+            })
+        })
+        .await
+        .unwrap();
+}
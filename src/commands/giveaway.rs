@@ -258,24 +258,12 @@ pub async fn handle_component_interaction(ctx: &Context, interaction: Interactio
 
 pub async fn handle_interaction(ctx: &Context, interaction: ApplicationCommandInteraction) {
     let db = ctx.get_db().await;
+    let config = ctx.get_config().await;
 
-    let giveaway_emoji: char = std::env::var("GIVEAWAY_REACTION_EMOJI")
-        .unwrap_or("🎉".to_string())
-        .parse()
-        .expect("GIVEAWAY_REACTION_EMOJI is not a valid char");
-
-    let default_duration: i64 = std::env::var("GIVEAWAY_DEFAULT_DURATION")
-        .unwrap_or("3600".to_string())
-        .parse()
-        .expect("GIVEAWAY_DEFAULT_DURATION is not a valid integer");
-
-    let default_winners: i64 = std::env::var("GIVEAWAY_DEFAULT_WINNERS")
-        .unwrap_or("1".to_string())
-        .parse()
-        .expect("GIVEAWAY_DEFAULT_WINNERS is not a valid integer");
-
-    let default_prize: String =
-        std::env::var("GIVEAWAY_DEFAULT_PRIZE").unwrap_or("Nothing".to_string());
+    let giveaway_emoji = config.giveaway_reaction_emoji;
+    let default_duration = config.giveaway_default_duration;
+    let default_winners = config.giveaway_default_winners;
+    let default_prize = config.giveaway_default_prize.clone();
 
     ensure_offset_map(ctx).await;
     let mut data = ctx.data.write().await;
@@ -1,5 +1,11 @@
+pub mod blacklist;
+pub mod councilstats;
 pub mod giveaway;
 pub mod links;
+pub mod moderate;
+pub mod modlog;
 pub mod owner;
+pub mod reports;
 pub mod role;
 pub mod vote;
+pub mod warnings;
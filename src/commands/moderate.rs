@@ -0,0 +1,455 @@
+//! `/mod`: actions a moderator can take at once, without waiting for a council vote
+
+use std::collections::HashMap;
+
+use serenity::{
+    model::{
+        channel::{Channel, ChannelType, PermissionOverwrite, PermissionOverwriteType},
+        id::{ChannelId, RoleId},
+        interactions::{
+            application_command::{
+                ApplicationCommandInteraction, ApplicationCommandInteractionDataOption,
+            },
+            InteractionApplicationCommandCallbackDataFlags, InteractionResponseType,
+        },
+        permissions::Permissions,
+        user::User,
+    },
+    prelude::Context,
+};
+
+use crate::{
+    extensions::*,
+    moderation::{
+        action::{self, Action},
+        log_action, log_channel_action, ActionKind, ActionSource,
+    },
+    voting::is_moderator,
+};
+
+/// Discord only deletes messages younger than this in bulk
+const BULK_DELETE_DAYS: i64 = 14;
+
+/// Discord doesn't accept timeouts longer than 28 days
+pub const MAX_TIMEOUT_MINUTES: i64 = 28 * 24 * 60;
+
+async fn respond(ctx: &Context, interaction: &ApplicationCommandInteraction, content: String) {
+    interaction
+        .create_interaction_response(&ctx.http, |r| {
+            r.interaction_response_data(|d| {
+                d.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL);
+                d.content(content)
+            })
+        })
+        .await
+        .unwrap();
+}
+
+/// Tells the other moderators on the moderation channel what was done and by whom
+async fn post_notice(
+    ctx: &Context,
+    moderator: &User,
+    action: &str,
+    target: String,
+    reason: Option<&str>,
+) {
+    let config = ctx.get_config().await;
+    if let Err(e) = ChannelId(config.mod_channel_id)
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.color(serenity::utils::Color::ORANGE);
+                e.title(action);
+                e.field("Kohde", target, true);
+                e.field("Arvojäsen", format!("<@{}>", moderator.id.0), true);
+                if let Some(reason) = reason {
+                    e.description(reason);
+                }
+                e
+            })
+        })
+        .await
+    {
+        error!("Unable to post a moderation notice: {}", e);
+    }
+}
+
+fn user_option(options: &[ApplicationCommandInteractionDataOption]) -> User {
+    options
+        .by_name("user")
+        .and_then(|x| x.to_user())
+        .expect("Missing user option")
+        .0
+}
+
+fn reason_option(options: &[ApplicationCommandInteractionDataOption]) -> Option<String> {
+    options.by_name("reason").and_then(|x| x.to_string())
+}
+
+async fn silence(
+    ctx: &Context,
+    moderator: &User,
+    options: &[ApplicationCommandInteractionDataOption],
+) -> Result<String, anyhow::Error> {
+    let user = user_option(options);
+    let reason = reason_option(options);
+    if ctx.get_db().await.is_silenced(user.id.0).await? {
+        return Ok(format!("<@{}> on jo hiljennetty", user.id.0));
+    }
+    let report = action::execute(
+        ctx,
+        Action::Silence {
+            user_id: user.id.0,
+            cause: String::from("arvojäsenen toimesta"),
+        },
+        Some(moderator.id.0),
+        ActionSource::Manual,
+        reason.clone(),
+    )
+    .await
+    .into_result()?;
+    post_notice(
+        ctx,
+        moderator,
+        "Jäsen hiljennetty",
+        format!("<@{}>, {}", user.id.0, user.tag()),
+        reason.as_deref(),
+    )
+    .await;
+    Ok(format!("<@{}>: {}", user.id.0, report.describe()))
+}
+
+async fn unsilence(
+    ctx: &Context,
+    moderator: &User,
+    options: &[ApplicationCommandInteractionDataOption],
+) -> Result<String, anyhow::Error> {
+    let user = user_option(options);
+    let reason = reason_option(options);
+    if !ctx.get_db().await.is_silenced(user.id.0).await? {
+        return Ok(format!("<@{}> ei ole hiljennetty", user.id.0));
+    }
+    let rules_channel_id = ctx.get_config().await.rules_channel_id;
+    let report = action::execute(
+        ctx,
+        Action::Unsilence {
+            user_id: user.id.0,
+            notice: format!(
+                "Arvojäsenet ovat purkaneet hiljennyksesi ja voit taas keskustella palvelimella. Tarkistathan sääntömme kanavalta <#{}>.",
+                rules_channel_id
+            ),
+        },
+        Some(moderator.id.0),
+        ActionSource::Manual,
+        reason.clone(),
+    )
+    .await
+    .into_result()?;
+    post_notice(
+        ctx,
+        moderator,
+        "Hiljennys purettu",
+        format!("<@{}>, {}", user.id.0, user.tag()),
+        reason.as_deref(),
+    )
+    .await;
+    Ok(format!("<@{}>: {}", user.id.0, report.describe()))
+}
+
+async fn purge(
+    ctx: &Context,
+    moderator: &User,
+    channel_id: ChannelId,
+    options: &[ApplicationCommandInteractionDataOption],
+) -> Result<String, anyhow::Error> {
+    let count = options
+        .by_name("count")
+        .and_then(|x| x.to_i64())
+        .expect("Missing count option")
+        .clamp(1, 100) as usize;
+    let user = options
+        .by_name("user")
+        .and_then(|x| x.to_user())
+        .map(|(user, _)| user);
+    let oldest = chrono::Utc::now() - chrono::Duration::days(BULK_DELETE_DAYS);
+    let messages = channel_id
+        .messages(&ctx.http, |r| r.limit(100))
+        .await?
+        .into_iter()
+        .filter(|m| user.as_ref().is_none_or(|u| m.author.id == u.id))
+        .filter(|m| *m.timestamp > oldest)
+        .take(count)
+        .collect::<Vec<_>>();
+    if messages.is_empty() {
+        return Ok(String::from("Poistettavia viestejä ei löytynyt"));
+    }
+    channel_id
+        .delete_messages(&ctx.http, messages.iter().map(|m| m.id))
+        .await?;
+
+    let mut authors = HashMap::new();
+    for message in &messages {
+        *authors.entry(message.author.id.0).or_insert(0) += 1;
+    }
+    for (author, deleted) in &authors {
+        log_action(
+            ctx,
+            *author,
+            Some(moderator.id.0),
+            ActionKind::Delete,
+            ActionSource::Manual,
+            Some(format!(
+                "{} viestiä poistettu kanavalta <#{}>",
+                deleted, channel_id.0
+            )),
+            None,
+        )
+        .await;
+    }
+    post_notice(
+        ctx,
+        moderator,
+        "Viestejä poistettu",
+        format!("<#{}>", channel_id.0),
+        Some(&format!(
+            "{} viestiä{}",
+            messages.len(),
+            user.as_ref()
+                .map(|u| format!(" käyttäjältä <@{}>", u.id.0))
+                .unwrap_or_default()
+        )),
+    )
+    .await;
+    Ok(format!("{} viestiä poistettu", messages.len()))
+}
+
+async fn timeout(
+    ctx: &Context,
+    moderator: &User,
+    options: &[ApplicationCommandInteractionDataOption],
+) -> Result<String, anyhow::Error> {
+    let config = ctx.get_config().await;
+    let user = user_option(options);
+    let reason = reason_option(options);
+    let minutes = options
+        .by_name("minutes")
+        .and_then(|x| x.to_i64())
+        .expect("Missing minutes option");
+    if !(1..=MAX_TIMEOUT_MINUTES).contains(&minutes) {
+        return Ok(format!(
+            "Jäähyn on oltava 1–{} minuuttia",
+            MAX_TIMEOUT_MINUTES
+        ));
+    }
+    let report = action::execute(
+        ctx,
+        Action::Timeout {
+            user_id: user.id.0,
+            until: chrono::Utc::now() + chrono::Duration::minutes(minutes),
+            notice: Some(format!(
+                "Sait arvojäseniltä {} minuutin jäähyn{}. Tarkistathan sääntömme kanavalta <#{}>.",
+                minutes,
+                reason
+                    .as_ref()
+                    .map(|r| format!(": {}", r))
+                    .unwrap_or_default(),
+                config.rules_channel_id
+            )),
+        },
+        Some(moderator.id.0),
+        ActionSource::Manual,
+        reason.clone(),
+    )
+    .await
+    .into_result()?;
+    post_notice(
+        ctx,
+        moderator,
+        &format!("{} minuutin jäähy", minutes),
+        format!("<@{}>, {}", user.id.0, user.tag()),
+        reason.as_deref(),
+    )
+    .await;
+    Ok(format!("<@{}>: {}", user.id.0, report.describe()))
+}
+
+async fn slowmode(
+    ctx: &Context,
+    moderator: &User,
+    channel_id: ChannelId,
+    options: &[ApplicationCommandInteractionDataOption],
+) -> Result<String, anyhow::Error> {
+    let channel_id = options
+        .by_name("channel")
+        .and_then(|x| x.to_channel())
+        .map_or(channel_id, |c| c.id);
+    let seconds = options
+        .by_name("seconds")
+        .and_then(|x| x.to_i64())
+        .expect("Missing seconds option")
+        .max(0) as u64;
+    channel_id
+        .edit(&ctx.http, |c| c.rate_limit_per_user(seconds))
+        .await?;
+    let description = if seconds == 0 {
+        String::from("Hidastustila poistettu")
+    } else {
+        format!("{} sekuntia viestien välillä", seconds)
+    };
+    log_channel_action(
+        ctx,
+        channel_id.0,
+        moderator.id.0,
+        ActionKind::Slowmode,
+        Some(description.clone()),
+    )
+    .await;
+    post_notice(
+        ctx,
+        moderator,
+        "Hidastustila",
+        format!("<#{}>", channel_id.0),
+        Some(&description),
+    )
+    .await;
+    Ok(format!("<#{}>: {}", channel_id.0, description))
+}
+
+/// Locks or unlocks a channel by denying @everyone from sending messages on it. Threads are
+/// locked as threads.
+async fn lock(
+    ctx: &Context,
+    moderator: &User,
+    channel_id: ChannelId,
+    options: &[ApplicationCommandInteractionDataOption],
+) -> Result<String, anyhow::Error> {
+    let config = ctx.get_config().await;
+    let channel_id = options
+        .by_name("channel")
+        .and_then(|x| x.to_channel())
+        .map_or(channel_id, |c| c.id);
+    let locked = options
+        .by_name("locked")
+        .and_then(|x| x.to_bool())
+        .unwrap_or(true);
+    let Channel::Guild(channel) = ctx.http.get_channel(channel_id.0).await? else {
+        return Ok(String::from("Vain palvelimen kanavia voi lukita"));
+    };
+    if matches!(
+        channel.kind,
+        ChannelType::PublicThread | ChannelType::PrivateThread | ChannelType::NewsThread
+    ) {
+        channel_id
+            .edit_thread(&ctx.http, |t| t.locked(locked))
+            .await?;
+    } else {
+        let everyone = PermissionOverwriteType::Role(RoleId(config.guild_id));
+        let (mut allow, mut deny) = channel
+            .permission_overwrites
+            .iter()
+            .find(|o| o.kind == everyone)
+            .map_or((Permissions::empty(), Permissions::empty()), |o| {
+                (o.allow, o.deny)
+            });
+        let send = Permissions::SEND_MESSAGES | Permissions::SEND_MESSAGES_IN_THREADS;
+        if locked {
+            allow.remove(send);
+            deny.insert(send);
+        } else {
+            deny.remove(send);
+        }
+        channel_id
+            .create_permission(
+                &ctx.http,
+                &PermissionOverwrite {
+                    allow,
+                    deny,
+                    kind: everyone,
+                },
+            )
+            .await?;
+    }
+    let reason = reason_option(options);
+    log_channel_action(
+        ctx,
+        channel_id.0,
+        moderator.id.0,
+        if locked {
+            ActionKind::LockChannel
+        } else {
+            ActionKind::UnlockChannel
+        },
+        reason.clone(),
+    )
+    .await;
+    post_notice(
+        ctx,
+        moderator,
+        if locked {
+            "Kanava lukittu"
+        } else {
+            "Kanavan lukitus avattu"
+        },
+        format!("<#{}>", channel_id.0),
+        reason.as_deref(),
+    )
+    .await;
+    Ok(format!(
+        "<#{}> {}",
+        channel_id.0,
+        if locked { "lukittu" } else { "avattu" }
+    ))
+}
+
+pub async fn handle_interaction(ctx: &Context, interaction: ApplicationCommandInteraction) {
+    if !is_moderator(ctx, &interaction.user).await {
+        respond(
+            ctx,
+            &interaction,
+            "Vain arvojäsenet voivat käyttää moderointikomentoja".to_string(),
+        )
+        .await;
+        return;
+    }
+
+    let option = interaction
+        .data
+        .options
+        .first()
+        .expect("Mod subcommand missing");
+    let moderator = &interaction.user;
+    let channel_id = interaction.channel_id;
+    let options = &option.options;
+    info!("/mod {} by {}", option.name, moderator.tag());
+    // Purging and retried requests can take longer than Discord waits for the first response
+    interaction
+        .create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::DeferredChannelMessageWithSource);
+            r.interaction_response_data(|d| {
+                d.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
+            })
+        })
+        .await
+        .unwrap();
+    let result = match option.name.as_str() {
+        "silence" => silence(ctx, moderator, options).await,
+        "unsilence" => unsilence(ctx, moderator, options).await,
+        "purge" => purge(ctx, moderator, channel_id, options).await,
+        "timeout" => timeout(ctx, moderator, options).await,
+        "slowmode" => slowmode(ctx, moderator, channel_id, options).await,
+        "lock" => lock(ctx, moderator, channel_id, options).await,
+        other => Err(anyhow::anyhow!("tuntematon alikomento `{}`", other)),
+    };
+    let content = match result {
+        Ok(content) => content,
+        Err(e) => {
+            error!("/mod {} failed: {}", option.name, e);
+            format!("Toimenpide epäonnistui: {}", e)
+        }
+    };
+    if let Err(e) = interaction
+        .edit_original_interaction_response(&ctx.http, |r| r.content(content))
+        .await
+    {
+        error!("Unable to answer /mod {}: {}", option.name, e);
+    }
+}
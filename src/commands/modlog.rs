@@ -0,0 +1,193 @@
+use serenity::{
+    builder::{CreateComponents, CreateEmbed},
+    model::{
+        interactions::{
+            message_component::{ButtonStyle, MessageComponentInteraction},
+            InteractionApplicationCommandCallbackDataFlags, InteractionResponseType,
+        },
+        prelude::application_command::ApplicationCommandInteraction,
+    },
+    prelude::Context,
+};
+
+use crate::{database::Database, extensions::*, voting::is_moderator};
+
+const PAGE_SIZE: i64 = 10;
+
+fn describe_kind(kind: &str) -> &str {
+    match kind {
+        "delete" => "Viesti poistettu",
+        "timeout" => "Jäähy",
+        "silence" => "Hiljennys",
+        "unsilence" => "Hiljennys poistettu",
+        "block_reporter" => "Ilmiantajaksi estetty",
+        "hide_name" => "Nimi piilotettu",
+        "warn" => "Varoitus",
+        "kick" => "Potkaistu",
+        "lock_thread" => "Ketju lukittu",
+        "slowmode" => "Hidastustila",
+        "lock_channel" => "Kanava lukittu",
+        "unlock_channel" => "Kanavan lukitus avattu",
+        _ => kind,
+    }
+}
+
+fn describe_source(source: &str) -> &str {
+    match source {
+        "council" => "neuvoston äänestys",
+        "manual" => "käsin",
+        "automod" => "automaattinen",
+        "blacklist" => "estolista",
+        "warnings" => "varoitusten porrastus",
+        "expiry" => "vanhentunut",
+        _ => source,
+    }
+}
+
+fn generate_components(user_id: u64, offset: i64, total: i64) -> CreateComponents {
+    let mut c = CreateComponents(Vec::new());
+    c.create_action_row(|r| {
+        r.create_button(|b| {
+            b.style(ButtonStyle::Secondary);
+            b.custom_id(format!(
+                "MODLOG_{}_{}",
+                user_id,
+                (offset - PAGE_SIZE).max(0)
+            ));
+            b.label("Edellinen sivu");
+            b.disabled(offset == 0)
+        });
+        r.create_button(|b| {
+            b.style(ButtonStyle::Secondary);
+            b.custom_id(format!("MODLOG_{}_{}", user_id, offset + PAGE_SIZE));
+            b.label("Seuraava sivu");
+            b.disabled(offset + PAGE_SIZE >= total)
+        })
+    });
+    c
+}
+
+/// One page of the history of the user, newest first
+async fn generate_page(
+    db: &Database,
+    user_id: u64,
+    offset: i64,
+) -> Result<(CreateEmbed, CreateComponents), anyhow::Error> {
+    let total = db.count_moderation_actions(user_id).await?;
+    let actions = db
+        .get_moderation_actions(user_id, PAGE_SIZE, offset)
+        .await?;
+
+    let lines = actions
+        .iter()
+        .map(|a| {
+            format!(
+                "`#{}` <t:{}:f> **{}** ({}){}{}{}",
+                a.id,
+                a.action_time.timestamp(),
+                describe_kind(&a.kind),
+                describe_source(&a.source),
+                a.actor_id
+                    .map(|id| format!(", <@{}>", id))
+                    .unwrap_or_default(),
+                a.expires_at
+                    .map(|t| format!(", päättyy <t:{}:R>", t.timestamp()))
+                    .unwrap_or_default(),
+                a.reason
+                    .as_ref()
+                    .map(|r| format!("\n> {}", r.chars().take(200).collect::<String>()))
+                    .unwrap_or_default(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut embed = CreateEmbed::default();
+    embed.title("Moderointiloki");
+    embed.description(if lines.is_empty() {
+        format!("Käyttäjälle <@{}> ei ole kirjattu toimenpiteitä", user_id)
+    } else {
+        format!("<@{}>\n\n{}", user_id, lines.join("\n"))
+    });
+    if total > 0 {
+        embed.footer(|f| {
+            f.text(format!(
+                "Sivu {}/{}, yhteensä {} toimenpidettä",
+                offset / PAGE_SIZE + 1,
+                (total + PAGE_SIZE - 1) / PAGE_SIZE,
+                total
+            ))
+        });
+    }
+    Ok((embed, generate_components(user_id, offset, total)))
+}
+
+pub async fn handle_interaction(ctx: &Context, interaction: ApplicationCommandInteraction) {
+    if !is_moderator(ctx, &interaction.user).await {
+        interaction
+            .create_interaction_response(&ctx.http, |r| {
+                r.interaction_response_data(|d| {
+                    d.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL);
+                    d.content("Vain arvojäsenet voivat tarkastella moderointilokia")
+                })
+            })
+            .await
+            .unwrap();
+        return;
+    }
+
+    let (user, _) = interaction
+        .data
+        .options
+        .by_name("user")
+        .and_then(|x| x.to_user())
+        .expect("Missing user option");
+    let db = ctx.get_db().await;
+    let (embed, components) = generate_page(&db, user.id.0, 0).await.unwrap();
+    interaction
+        .create_interaction_response(&ctx.http, |r| {
+            r.interaction_response_data(|d| {
+                d.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL);
+                d.add_embed(embed);
+                d.set_components(components)
+            })
+        })
+        .await
+        .unwrap();
+}
+
+/// Handles the page buttons, whose ids are `MODLOG_<user id>_<offset>`
+pub async fn handle_component_interaction(ctx: &Context, component: MessageComponentInteraction) {
+    if !is_moderator(ctx, &component.user).await {
+        component
+            .create_interaction_response(&ctx.http, |r| {
+                r.interaction_response_data(|d| {
+                    d.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL);
+                    d.content("Vain arvojäsenet voivat tarkastella moderointilokia")
+                })
+            })
+            .await
+            .unwrap();
+        return;
+    }
+    let mut parts = component.data.custom_id.split('_').skip(1);
+    let (Some(Ok(user_id)), Some(Ok(offset))) = (
+        parts.next().map(str::parse::<u64>),
+        parts.next().map(str::parse::<i64>),
+    ) else {
+        debug!("Unknown interaction: {}", component.data.custom_id);
+        return;
+    };
+
+    let db = ctx.get_db().await;
+    let (embed, components) = generate_page(&db, user_id, offset).await.unwrap();
+    component
+        .create_interaction_response(&ctx.http, |r| {
+            r.kind(InteractionResponseType::UpdateMessage);
+            r.interaction_response_data(|d| {
+                d.set_embeds(vec![embed]);
+                d.set_components(components)
+            })
+        })
+        .await
+        .unwrap();
+}
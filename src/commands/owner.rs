@@ -4,7 +4,7 @@ use serenity::{
     prelude::*,
 };
 
-use crate::{events::activity_award::display_winner, extensions::*, ShardManagerContainer};
+use crate::{events::activity_award::display_winner, ShardManagerContainer};
 
 #[command]
 #[owners_only]
@@ -29,8 +29,7 @@ async fn award_ceremony(ctx: &Context, _msg: &Message, mut args: Args) -> Comman
         Ok(a) => a,
         _ => 0,
     };
-    let db = ctx.get_db().await;
     let http = ctx.http.to_owned();
-    display_winner(http, db, offset).await;
+    display_winner(http, ctx.data.clone(), offset).await;
     Ok(())
 }
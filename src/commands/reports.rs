@@ -0,0 +1,96 @@
+use serenity::{
+    model::{
+        interactions::InteractionApplicationCommandCallbackDataFlags,
+        prelude::application_command::ApplicationCommandInteraction,
+    },
+    prelude::Context,
+};
+
+use crate::{
+    database::Database,
+    extensions::*,
+    models::CouncilVoting,
+    moderation::council::{CouncilAction, VotingResolution},
+};
+
+/// How many of the member's most recent reports are listed
+const LISTED_REPORTS: i64 = 10;
+
+/// What came of the report, without naming the voters
+async fn describe_outcome(db: &Database, voting: &CouncilVoting) -> String {
+    match voting.resolution.parse() {
+        Ok(VotingResolution::Pending) => format!(
+            "Käsittelyssä, äänestys päättyy <t:{}:R>",
+            voting.deadline.timestamp()
+        ),
+        Ok(VotingResolution::Actioned) => {
+            let outcomes = db
+                .get_voting_thresholds(voting.vote_message_id)
+                .await
+                .unwrap_or_default()
+                .iter()
+                .filter(|t| t.executed_at.is_some())
+                .filter_map(|t| CouncilAction::from_id(t.vote_type).and_then(|a| a.outcome()))
+                .collect::<Vec<_>>();
+            if outcomes.is_empty() {
+                String::from("Käsitelty")
+            } else {
+                format!("Käsitelty: {}", outcomes.join(", "))
+            }
+        }
+        Ok(VotingResolution::Dismissed) => String::from("Hylätty"),
+        Ok(VotingResolution::Expired) => String::from("Vanhentunut ilman toimenpiteitä"),
+        Err(_) => voting.resolution.clone(),
+    }
+}
+
+/// Lists the member's own recent reports and their outcomes, and turns the notifications about
+/// resolved reports on or off
+pub async fn handle_interaction(ctx: &Context, interaction: ApplicationCommandInteraction) {
+    let db = ctx.get_db().await;
+    let guild_id = ctx.get_config().await.guild_id;
+    let user_id = interaction.user.id.0;
+
+    let mut description = String::new();
+    if let Some(enabled) = interaction
+        .data
+        .options
+        .by_name("notifications")
+        .and_then(|x| x.to_bool())
+    {
+        db.set_report_notifications(user_id, enabled).await.unwrap();
+        description.push_str(if enabled {
+            "Saat jatkossa yksityisviestin, kun ilmoituksesi on käsitelty.\n\n"
+        } else {
+            "Et saa enää yksityisviestejä ilmoitustesi käsittelystä.\n\n"
+        });
+    }
+
+    let reports = db.get_user_reports(user_id, LISTED_REPORTS).await.unwrap();
+    if reports.is_empty() {
+        description.push_str("Et ole ilmiantanut viestejä");
+    }
+    for voting in &reports {
+        description.push_str(&format!(
+            "<t:{}:d> [Viesti](https://discord.com/channels/{}/{}/{}): {}\n",
+            voting.suspect_message_send_time.timestamp(),
+            guild_id,
+            voting.suspect_message_channel_id,
+            voting.suspect_message_id,
+            describe_outcome(&db, voting).await
+        ));
+    }
+
+    interaction
+        .create_interaction_response(&ctx.http, |r| {
+            r.interaction_response_data(|d| {
+                d.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL);
+                d.embed(|e| {
+                    e.title("Ilmoituksesi");
+                    e.description(description)
+                })
+            })
+        })
+        .await
+        .unwrap();
+}
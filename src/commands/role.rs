@@ -10,30 +10,13 @@ use serenity::{
     prelude::Context,
 };
 
+use crate::extensions::*;
+
 pub async fn handle_interaction(ctx: &Context, intercation: ApplicationCommandInteraction) {
-    let guild_id: u64 = std::env::var("GUILD_ID")
-        .expect("NO GUILD_ID in .env")
-        .parse()
-        .unwrap();
+    let guild_id = ctx.get_config().await.guild_id;
     let mut guild_roles = ctx.http.get_guild_roles(guild_id).await.unwrap();
-    let roles = match std::fs::read_to_string("self_service_roles.txt") {
-        Ok(s) => s,
-        Err(e) => {
-            match e.kind() {
-                std::io::ErrorKind::NotFound => {
-                    std::fs::File::create("self_service_roles.txt")
-                        .expect("Unable to create self_service_roles.txt");
-                }
-                _ => panic!("Unable to access self_service_roles.txt"),
-            }
-            String::new()
-        }
-    };
-    let roles: Vec<u64> = roles
-        .lines()
-        .map(|l| l.trim().parse::<u64>().unwrap_or(0))
-        .collect();
-    guild_roles.retain(|r| roles.contains(&r.id.0));
+    let roles = ctx.get_self_service_roles().await;
+    guild_roles.retain(|r| roles.0.contains(&r.id.0));
     if guild_roles.is_empty() {
         intercation
             .create_interaction_response(&ctx.http, |r| {
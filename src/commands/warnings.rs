@@ -0,0 +1,127 @@
+use serenity::{
+    model::{
+        interactions::InteractionApplicationCommandCallbackDataFlags,
+        prelude::application_command::ApplicationCommandInteraction,
+    },
+    prelude::Context,
+};
+
+use crate::{extensions::*, moderation::warnings, voting::is_moderator};
+
+async fn respond(ctx: &Context, interaction: &ApplicationCommandInteraction, content: String) {
+    interaction
+        .create_interaction_response(&ctx.http, |r| {
+            r.interaction_response_data(|d| {
+                d.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL);
+                d.content(content)
+            })
+        })
+        .await
+        .unwrap();
+}
+
+pub async fn warn(ctx: &Context, interaction: ApplicationCommandInteraction) {
+    if !is_moderator(ctx, &interaction.user).await {
+        respond(
+            ctx,
+            &interaction,
+            "Vain arvojäsenet voivat antaa varoituksia".to_string(),
+        )
+        .await;
+        return;
+    }
+
+    let options = &interaction.data.options;
+    let (user, _) = options
+        .by_name("user")
+        .and_then(|x| x.to_user())
+        .expect("Missing user option");
+    let reason = options
+        .by_name("reason")
+        .and_then(|x| x.to_string())
+        .expect("Missing reason option");
+    if user.bot {
+        respond(ctx, &interaction, "Botteja ei voi varoittaa".to_string()).await;
+        return;
+    }
+
+    let (active, escalation) = warnings::warn(ctx, &user, interaction.user.id.0, &reason)
+        .await
+        .unwrap();
+    info!(
+        "{} warned {}, {} active warnings",
+        interaction.user.id.0, user.id.0, active
+    );
+    respond(
+        ctx,
+        &interaction,
+        format!(
+            "Varoitus annettu käyttäjälle <@{}>, voimassa olevia varoituksia {}{}",
+            user.id.0,
+            active,
+            escalation
+                .map(|e| format!("\nSeuraus: {}", e))
+                .unwrap_or_default()
+        ),
+    )
+    .await;
+}
+
+pub async fn list(ctx: &Context, interaction: ApplicationCommandInteraction) {
+    if !is_moderator(ctx, &interaction.user).await {
+        respond(
+            ctx,
+            &interaction,
+            "Vain arvojäsenet voivat tarkastella varoituksia".to_string(),
+        )
+        .await;
+        return;
+    }
+
+    let (user, _) = interaction
+        .data
+        .options
+        .by_name("user")
+        .and_then(|x| x.to_user())
+        .expect("Missing user option");
+    let warnings = ctx.get_db().await.get_warnings(user.id.0).await.unwrap();
+    let now = chrono::Local::now().naive_local();
+    let active = warnings.iter().filter(|w| w.expires_at > now).count();
+
+    let mut description = String::new();
+    for (shown, w) in warnings.iter().enumerate() {
+        let line = format!(
+            "`#{}` <t:{}:d> <@{}>{}\n> {}\n",
+            w.id,
+            w.issued_at.timestamp(),
+            w.issuer_id,
+            if w.expires_at > now {
+                format!(", päättyy <t:{}:R>", w.expires_at.timestamp())
+            } else {
+                String::from(", päättynyt")
+            },
+            w.reason.chars().take(200).collect::<String>()
+        );
+        if description.len() + line.len() > 4000 {
+            description.push_str(&format!("…ja {} muuta", warnings.len() - shown));
+            break;
+        }
+        description.push_str(&line);
+    }
+    if description.is_empty() {
+        description = String::from("Ei varoituksia");
+    }
+
+    interaction
+        .create_interaction_response(&ctx.http, |r| {
+            r.interaction_response_data(|d| {
+                d.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL);
+                d.embed(|e| {
+                    e.title(format!("Varoitukset: {} ({} voimassa)", user.tag(), active));
+                    e.description(description)
+                })
+            })
+        })
+        .await
+        .unwrap();
+}
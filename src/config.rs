@@ -0,0 +1,217 @@
+use std::{collections::HashMap, env, fmt::Display, str::FromStr, sync::Arc};
+
+use serenity::prelude::TypeMapKey;
+
+use crate::{
+    blacklist::BlacklistAction,
+    moderation::{council::CouncilActions, sticky::StickyRoleIds, warnings::EscalationLadder},
+};
+
+/// The bot configuration, loaded and validated once at startup.
+///
+/// Values are read from the environment (and `.env`) and from an optional TOML file. The file
+/// is read from the path in `CONFIG_FILE`, or from `config.toml` if it exists. Keys in the file
+/// are the lowercase versions of the environment variable names, and environment variables
+/// always take precedence over the file.
+pub struct Config {
+    pub database_url: String,
+    pub discord_token: String,
+    pub application_id: u64,
+    pub guild_id: u64,
+    pub mod_channel_id: u64,
+    pub award_channel_id: u64,
+    pub rules_channel_id: u64,
+    pub status_channel_id: Option<u64>,
+    pub no_reports_role_id: u64,
+    pub award_role_id: u64,
+    pub silenced_role_id: u64,
+    pub member_role_id: u64,
+    /// Roles given back to a member who leaves and rejoins, in addition to the silenced and no
+    /// reports roles
+    pub sticky_role_ids: StickyRoleIds,
+    pub giveaway_default_duration: i64,
+    pub giveaway_default_winners: i64,
+    pub giveaway_default_prize: String,
+    pub giveaway_reaction_emoji: char,
+    /// Actions for blacklist hits outside message content. When unset, the action of the
+    /// matching pattern is used.
+    pub blacklist_embed_action: Option<BlacklistAction>,
+    pub blacklist_attachment_action: Option<BlacklistAction>,
+    pub blacklist_thread_action: Option<BlacklistAction>,
+    pub blacklist_name_action: Option<BlacklistAction>,
+    /// The number of mentioned users and roles above which a message is treated as spam
+    pub antispam_max_mentions: usize,
+    /// The number of channels the same message has to be sent to within
+    /// `antispam_duplicate_seconds` to be treated as spam
+    pub antispam_duplicate_channels: usize,
+    pub antispam_duplicate_seconds: u64,
+    /// The number of messages a user can send to a channel within `ratelimit_seconds` before
+    /// being timed out for `ratelimit_timeout_minutes`
+    pub ratelimit_messages: usize,
+    pub ratelimit_seconds: u64,
+    pub ratelimit_timeout_minutes: i64,
+    /// The stricter limit for members who joined within `raid_join_minutes`
+    pub raid_ratelimit_messages: usize,
+    pub raid_join_minutes: i64,
+    pub warning_expiry_days: i64,
+    pub warning_ladder: EscalationLadder,
+    pub silence_duration_days: i64,
+    /// The actions offered on council votings and the votes each of them requires
+    pub council_actions: CouncilActions,
+    pub council_timeout_minutes: i64,
+    /// How long a council voting stays open before it expires
+    pub council_vote_hours: i64,
+    /// Where the attachments of reported messages are saved
+    pub report_archive_dir: String,
+    /// Larger attachments are linked to instead of downloaded
+    pub report_attachment_max_bytes: u64,
+    /// How many preceding messages are shown with a reported message
+    pub report_context_messages: u64,
+    /// Reporters whose reputation score is below this can only report once every
+    /// `reporter_ratelimit_hours`
+    pub reporter_min_score: f32,
+    pub reporter_ratelimit_hours: i64,
+}
+
+impl TypeMapKey for Config {
+    type Value = Arc<Config>;
+}
+
+impl Config {
+    /// Load the configuration, collecting every missing or malformed key into a single error
+    pub fn load() -> Result<Self, anyhow::Error> {
+        let mut loader = ConfigLoader::new()?;
+        let config = Self {
+            database_url: loader.required("DATABASE_URL"),
+            discord_token: loader.required("DISCORD_TOKEN"),
+            application_id: loader.required("APPLICATION_ID"),
+            guild_id: loader.required("GUILD_ID"),
+            mod_channel_id: loader.required("MOD_CHANNEL_ID"),
+            award_channel_id: loader.required("AWARD_CHANNEL_ID"),
+            rules_channel_id: loader.required("RULES_CHANNEL_ID"),
+            status_channel_id: loader.optional("STATUS_CHANNEL_ID"),
+            no_reports_role_id: loader.required("NO_REPORTS_ROLE_ID"),
+            award_role_id: loader.required("AWARD_ROLE_ID"),
+            silenced_role_id: loader.required("SILENCED_ROLE_ID"),
+            member_role_id: loader.required("MEMBER_ROLE_ID"),
+            sticky_role_ids: loader.with_default("STICKY_ROLE_IDS", StickyRoleIds::default()),
+            giveaway_default_duration: loader.with_default("GIVEAWAY_DEFAULT_DURATION", 3600),
+            giveaway_default_winners: loader.with_default("GIVEAWAY_DEFAULT_WINNERS", 1),
+            giveaway_default_prize: loader
+                .with_default("GIVEAWAY_DEFAULT_PRIZE", String::from("Nothing")),
+            giveaway_reaction_emoji: loader.with_default("GIVEAWAY_REACTION_EMOJI", '🎉'),
+            blacklist_embed_action: loader.optional("BLACKLIST_EMBED_ACTION"),
+            blacklist_attachment_action: loader.optional("BLACKLIST_ATTACHMENT_ACTION"),
+            blacklist_thread_action: loader.optional("BLACKLIST_THREAD_ACTION"),
+            blacklist_name_action: loader.optional("BLACKLIST_NAME_ACTION"),
+            antispam_max_mentions: loader.with_default("ANTISPAM_MAX_MENTIONS", 5),
+            antispam_duplicate_channels: loader.with_default("ANTISPAM_DUPLICATE_CHANNELS", 3),
+            antispam_duplicate_seconds: loader.with_default("ANTISPAM_DUPLICATE_SECONDS", 10),
+            ratelimit_messages: loader.with_default("RATELIMIT_MESSAGES", 8),
+            ratelimit_seconds: loader.with_default("RATELIMIT_SECONDS", 10),
+            ratelimit_timeout_minutes: loader.with_default("RATELIMIT_TIMEOUT_MINUTES", 10),
+            raid_ratelimit_messages: loader.with_default("RAID_RATELIMIT_MESSAGES", 4),
+            raid_join_minutes: loader.with_default("RAID_JOIN_MINUTES", 10),
+            warning_expiry_days: loader.with_default("WARNING_EXPIRY_DAYS", 30),
+            warning_ladder: loader.with_default("WARNING_LADDER", EscalationLadder::default()),
+            silence_duration_days: loader.with_default("SILENCE_DURATION_DAYS", 7),
+            council_actions: loader.with_default("COUNCIL_ACTIONS", CouncilActions::default()),
+            council_timeout_minutes: loader.with_default("COUNCIL_TIMEOUT_MINUTES", 60),
+            council_vote_hours: loader.with_default("COUNCIL_VOTE_HOURS", 48),
+            report_archive_dir: loader
+                .with_default("REPORT_ARCHIVE_DIR", String::from("report_archive")),
+            report_attachment_max_bytes: loader
+                .with_default("REPORT_ATTACHMENT_MAX_BYTES", 8 * 1024 * 1024),
+            report_context_messages: loader.with_default("REPORT_CONTEXT_MESSAGES", 3),
+            reporter_min_score: loader.with_default("REPORTER_MIN_SCORE", 0.25),
+            reporter_ratelimit_hours: loader.with_default("REPORTER_RATELIMIT_HOURS", 24),
+        };
+
+        if !loader.errors.is_empty() {
+            anyhow::bail!("Invalid configuration:\n{}", loader.errors.join("\n"));
+        }
+        Ok(config)
+    }
+}
+
+struct ConfigLoader {
+    file: HashMap<String, String>,
+    errors: Vec<String>,
+}
+
+impl ConfigLoader {
+    fn new() -> Result<Self, anyhow::Error> {
+        let (path, must_exist) = match env::var("CONFIG_FILE") {
+            Ok(p) => (p, true),
+            Err(_) => (String::from("config.toml"), false),
+        };
+
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(s) => s,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && !must_exist => String::new(),
+            Err(e) => anyhow::bail!("Unable to read config file {}: {}", path, e),
+        };
+
+        let table: toml::value::Table = toml::from_str(&contents)?;
+        let mut file = HashMap::new();
+        let mut errors = Vec::new();
+        for (key, value) in table {
+            let value = match value {
+                toml::Value::String(s) => s,
+                toml::Value::Integer(i) => i.to_string(),
+                toml::Value::Float(f) => f.to_string(),
+                toml::Value::Boolean(b) => b.to_string(),
+                _ => {
+                    errors.push(format!("{}: unsupported value type in {}", key, path));
+                    continue;
+                }
+            };
+            file.insert(key.to_lowercase(), value);
+        }
+
+        Ok(Self { file, errors })
+    }
+
+    fn lookup(&self, key: &str) -> Option<String> {
+        env::var(key)
+            .ok()
+            .or_else(|| self.file.get(&key.to_lowercase()).cloned())
+            .filter(|v| !v.trim().is_empty())
+    }
+
+    fn optional<T>(&mut self, key: &str) -> Option<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let value = self.lookup(key)?;
+        match value.trim().parse() {
+            Ok(v) => Some(v),
+            Err(e) => {
+                self.errors
+                    .push(format!("{}: invalid value `{}` ({})", key, value, e));
+                None
+            }
+        }
+    }
+
+    fn required<T>(&mut self, key: &str) -> T
+    where
+        T: FromStr + Default,
+        T::Err: Display,
+    {
+        if self.lookup(key).is_none() {
+            self.errors.push(format!("{}: missing", key));
+            return T::default();
+        }
+        self.optional(key).unwrap_or_default()
+    }
+
+    fn with_default<T>(&mut self, key: &str, default: T) -> T
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.optional(key).unwrap_or(default)
+    }
+}
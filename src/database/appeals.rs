@@ -0,0 +1,123 @@
+use diesel::prelude::*;
+
+use super::Database;
+use crate::models::*;
+
+impl Database {
+    pub async fn new_appeal(&self, appeal: &NewAppeal) -> Result<usize, anyhow::Error> {
+        Ok(diesel::insert_into(crate::schema::Appeals::table)
+            .values(appeal)
+            .execute(&self.pool.get()?)?)
+    }
+
+    pub async fn get_appeal(&self, message_id: u64) -> Result<Appeal, anyhow::Error> {
+        use crate::schema::Appeals::dsl::*;
+        Ok(Appeals
+            .filter(vote_message_id.eq(message_id))
+            .first::<Appeal>(&self.pool.get()?)?)
+    }
+
+    /// The unresolved appeal of the user, if there is one
+    pub async fn get_pending_appeal(
+        &self,
+        target_id: u64,
+    ) -> Result<Option<Appeal>, anyhow::Error> {
+        use crate::schema::Appeals::dsl::*;
+        Ok(Appeals
+            .filter(user_id.eq(target_id))
+            .filter(resolution.is_null())
+            .first::<Appeal>(&self.pool.get()?)
+            .optional()?)
+    }
+
+    pub async fn get_appeal_votes(&self, appealid: i32) -> Result<Vec<AppealVote>, anyhow::Error> {
+        use crate::schema::AppealVotes::dsl::*;
+        Ok(AppealVotes
+            .filter(appeal_id.eq(appealid))
+            .load::<AppealVote>(&self.pool.get()?)?)
+    }
+
+    /// Each moderator has a single vote per appeal. Voting the same way again takes the vote
+    /// back and voting the other way changes it. Returns the votes of the appeal afterwards.
+    /// The appeal is locked for the toggle, so that concurrent clicks can't count twice.
+    pub async fn toggle_appeal_vote(
+        &self,
+        appealid: i32,
+        voter_id: u64,
+        new_vote_type: i32,
+    ) -> Result<Vec<AppealVote>, anyhow::Error> {
+        let conn = self.pool.get()?;
+        conn.transaction::<_, anyhow::Error, _>(|| {
+            {
+                use crate::schema::Appeals::dsl::*;
+                Appeals
+                    .filter(id.eq(appealid))
+                    .for_update()
+                    .first::<Appeal>(&conn)?;
+            }
+            use crate::schema::AppealVotes::dsl::*;
+            let existing = AppealVotes
+                .filter(appeal_id.eq(appealid))
+                .filter(voter_user_id.eq(voter_id))
+                .first::<AppealVote>(&conn)
+                .optional()?;
+            match existing {
+                Some(vote) if vote.vote_type == new_vote_type => {
+                    diesel::delete(AppealVotes.filter(id.eq(vote.id))).execute(&conn)?;
+                }
+                Some(vote) => {
+                    diesel::update(AppealVotes.filter(id.eq(vote.id)))
+                        .set(vote_type.eq(new_vote_type))
+                        .execute(&conn)?;
+                }
+                None => {
+                    // The unique (appeal, voter) index keeps a double click from counting
+                    // twice
+                    diesel::insert_or_ignore_into(AppealVotes)
+                        .values(&NewAppealVote {
+                            appeal_id: appealid,
+                            voter_user_id: voter_id,
+                            vote_type: new_vote_type,
+                        })
+                        .execute(&conn)?;
+                }
+            }
+            Ok(AppealVotes
+                .filter(appeal_id.eq(appealid))
+                .load::<AppealVote>(&conn)?)
+        })
+    }
+
+    /// Resolves the appeal unless it already is resolved. Returns 0 if it was, in which case the
+    /// resolution must not be carried out again.
+    pub async fn resolve_appeal(
+        &self,
+        appealid: i32,
+        appeal_resolution: &str,
+    ) -> Result<usize, anyhow::Error> {
+        use crate::schema::Appeals::dsl::*;
+        Ok(
+            diesel::update(Appeals.filter(id.eq(appealid)).filter(resolution.is_null()))
+                .set((
+                    resolution.eq(appeal_resolution),
+                    resolved_at.eq(chrono::Local::now().naive_local()),
+                ))
+                .execute(&self.pool.get()?)?,
+        )
+    }
+
+    /// Replaces the resolution claimed with `resolve_appeal`. `None` reopens the appeal.
+    pub async fn set_appeal_resolution(
+        &self,
+        appealid: i32,
+        appeal_resolution: Option<&str>,
+    ) -> Result<usize, anyhow::Error> {
+        use crate::schema::Appeals::dsl::*;
+        Ok(diesel::update(Appeals.filter(id.eq(appealid)))
+            .set((
+                resolution.eq(appeal_resolution),
+                resolved_at.eq(appeal_resolution.map(|_| chrono::Local::now().naive_local())),
+            ))
+            .execute(&self.pool.get()?)?)
+    }
+}
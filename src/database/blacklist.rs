@@ -0,0 +1,118 @@
+use diesel::prelude::*;
+
+use super::{last_insert_id, Database};
+use crate::{
+    blacklist::{compile_pattern, PatternFlags},
+    models::*,
+};
+
+impl Database {
+    pub async fn get_blacklist_entries(&self) -> Result<Vec<BlacklistEntry>, anyhow::Error> {
+        use crate::schema::BlacklistEntries::dsl::*;
+        Ok(BlacklistEntries
+            .filter(enabled.eq(true))
+            .order_by(id)
+            .load::<BlacklistEntry>(&self.pool.get()?)?)
+    }
+
+    pub async fn add_blacklist_entry(
+        &self,
+        pattern: &str,
+        added_by: u64,
+        reason: Option<String>,
+        action: &str,
+        timeout_minutes: Option<i32>,
+        flags: PatternFlags,
+    ) -> Result<i32, anyhow::Error> {
+        let entry = NewBlacklistEntry {
+            pattern: pattern.to_string(),
+            added_by,
+            added_at: chrono::Local::now().naive_local(),
+            reason,
+            action: action.to_string(),
+            timeout_minutes,
+            case_sensitive: flags.case_sensitive,
+            whole_word: flags.whole_word,
+            normalize: flags.normalize,
+        };
+        let conn = self.pool.get()?;
+        diesel::insert_into(crate::schema::BlacklistEntries::table)
+            .values(&entry)
+            .execute(&conn)?;
+        Ok(diesel::select(last_insert_id).first::<u64>(&conn)? as i32)
+    }
+
+    /// Disables the entry instead of deleting it so that the history of who added
+    /// which pattern is kept
+    pub async fn remove_blacklist_entry(&self, entry_id: i32) -> Result<usize, anyhow::Error> {
+        use crate::schema::BlacklistEntries::dsl::*;
+        Ok(
+            diesel::update(BlacklistEntries.filter(id.eq(entry_id).and(enabled.eq(true))))
+                .set(enabled.eq(false))
+                .execute(&self.pool.get()?)?,
+        )
+    }
+
+    /// Imports the patterns from the old `blacklist.txt` if the table has never been used
+    pub async fn import_blacklist_file(
+        &self,
+        path: &str,
+        added_by: u64,
+    ) -> Result<usize, anyhow::Error> {
+        let existing = {
+            use crate::schema::BlacklistEntries::dsl::*;
+            BlacklistEntries
+                .count()
+                .get_result::<i64>(&self.pool.get()?)?
+        };
+        if existing > 0 {
+            return Ok(0);
+        }
+
+        let contents = match std::fs::read_to_string(path) {
+            Ok(s) => s,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => return Err(e.into()),
+        };
+        // The file was compiled with `Regex::new`, so the patterns are kept case-sensitive
+        let flags = PatternFlags {
+            case_sensitive: true,
+            ..Default::default()
+        };
+        let now = chrono::Local::now().naive_local();
+        let entries = contents
+            .lines()
+            .filter(|l| !l.is_empty())
+            .filter(|l| match compile_pattern(l, flags) {
+                Ok(_) => true,
+                Err(_) => {
+                    info!("Skipping invalid regex in `{}`: {}", path, l);
+                    false
+                }
+            })
+            .map(|l| NewBlacklistEntry {
+                pattern: l.to_string(),
+                added_by,
+                added_at: now,
+                reason: Some(format!("Imported from {}", path)),
+                action: String::from("delete"),
+                timeout_minutes: None,
+                case_sensitive: flags.case_sensitive,
+                whole_word: flags.whole_word,
+                normalize: flags.normalize,
+            })
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            return Ok(0);
+        }
+        Ok(diesel::insert_into(crate::schema::BlacklistEntries::table)
+            .values(&entries)
+            .execute(&self.pool.get()?)?)
+    }
+
+    pub async fn log_blacklist_hit(&self, hit: &NewBlacklistHit) -> Result<usize, anyhow::Error> {
+        Ok(diesel::insert_into(crate::schema::BlacklistHits::table)
+            .values(hit)
+            .execute(&self.pool.get()?)?)
+    }
+}
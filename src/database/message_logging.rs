@@ -49,21 +49,9 @@ impl Database {
         &self,
         winner_count: i64,
         days_pre: i32,
+        excluded: &[u64],
     ) -> Result<Vec<(u64, i32)>, anyhow::Error> {
-        let blacklist = match std::fs::read_to_string("award_id_blacklist.txt") {
-            Ok(s) => s,
-            Err(e) => {
-                match e.kind() {
-                    std::io::ErrorKind::NotFound => {
-                        std::fs::File::create("award_id_blacklist.txt")
-                            .expect("Unable to create award_id_blacklist.txt");
-                    }
-                    _ => panic!("Unable to access award_id_blacklist.txt"),
-                }
-                String::new()
-            }
-        };
-        let blacklist = blacklist.lines();
+        let blacklist = excluded.iter().map(|user| user.to_string());
         let curdate =
             chrono::Local::today().naive_local() - chrono::Duration::days(days_pre.into());
 
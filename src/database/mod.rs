@@ -1,8 +1,13 @@
 use serenity::prelude::TypeMapKey;
+pub mod appeals;
+pub mod blacklist;
 pub mod giveaway;
 pub mod message_logging;
+pub mod moderation;
+pub mod sticky;
 pub mod vote;
 pub mod voting;
+pub mod warnings;
 
 use std::sync::Arc;
 
@@ -11,6 +16,11 @@ use diesel::{
     r2d2::{ConnectionManager, Pool},
 };
 
+no_arg_sql_function!(
+    last_insert_id,
+    diesel::sql_types::Unsigned<diesel::sql_types::Bigint>
+);
+
 #[derive(Clone)]
 pub struct Database {
     pool: Pool<ConnectionManager<MysqlConnection>>,
@@ -27,10 +37,8 @@ impl AsRef<Database> for Database {
 }
 
 impl Database {
-    pub async fn new() -> Self {
-        let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-
-        let manager = ConnectionManager::<MysqlConnection>::new(&database_url);
+    pub async fn new(database_url: &str) -> Self {
+        let manager = ConnectionManager::<MysqlConnection>::new(database_url);
         let pool = Pool::builder()
             .build(manager)
             .expect("Failed to create connection pool");
@@ -0,0 +1,39 @@
+use diesel::prelude::*;
+
+use super::Database;
+use crate::models::*;
+
+impl Database {
+    pub async fn log_moderation_action(
+        &self,
+        action: &NewModerationAction,
+    ) -> Result<usize, anyhow::Error> {
+        Ok(diesel::insert_into(crate::schema::ModerationActions::table)
+            .values(action)
+            .execute(&self.pool.get()?)?)
+    }
+
+    /// The actions taken against the user, newest first
+    pub async fn get_moderation_actions(
+        &self,
+        target_id: u64,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<ModerationAction>, anyhow::Error> {
+        use crate::schema::ModerationActions::dsl::*;
+        Ok(ModerationActions
+            .filter(user_id.eq(target_id))
+            .order_by(id.desc())
+            .limit(limit)
+            .offset(offset)
+            .load::<ModerationAction>(&self.pool.get()?)?)
+    }
+
+    pub async fn count_moderation_actions(&self, target_id: u64) -> Result<i64, anyhow::Error> {
+        use crate::schema::ModerationActions::dsl::*;
+        Ok(ModerationActions
+            .filter(user_id.eq(target_id))
+            .count()
+            .get_result::<i64>(&self.pool.get()?)?)
+    }
+}
@@ -0,0 +1,82 @@
+use diesel::prelude::*;
+
+use super::Database;
+use crate::models::*;
+
+impl Database {
+    pub async fn add_sticky_role(&self, user: u64, role: u64) -> Result<usize, anyhow::Error> {
+        Ok(
+            diesel::insert_or_ignore_into(crate::schema::StickyRoles::table)
+                .values(&NewStickyRole {
+                    user_id: user,
+                    role_id: role,
+                })
+                .execute(&self.pool.get()?)?,
+        )
+    }
+
+    pub async fn remove_sticky_role(&self, user: u64, role: u64) -> Result<usize, anyhow::Error> {
+        use crate::schema::StickyRoles::dsl::*;
+        Ok(diesel::delete(
+            StickyRoles
+                .filter(user_id.eq(user))
+                .filter(role_id.eq(role)),
+        )
+        .execute(&self.pool.get()?)?)
+    }
+
+    pub async fn get_sticky_roles(&self, user: u64) -> Result<Vec<u64>, anyhow::Error> {
+        use crate::schema::StickyRoles::dsl::*;
+        Ok(StickyRoles
+            .filter(user_id.eq(user))
+            .select(role_id)
+            .load::<u64>(&self.pool.get()?)?)
+    }
+
+    pub async fn get_sticky_member(
+        &self,
+        user: u64,
+    ) -> Result<Option<StickyMember>, anyhow::Error> {
+        use crate::schema::StickyMembers::dsl::*;
+        Ok(StickyMembers
+            .filter(user_id.eq(user))
+            .first::<StickyMember>(&self.pool.get()?)
+            .optional()?)
+    }
+
+    /// Makes sure the member has a row whose columns can be updated one at a time
+    fn ensure_sticky_member(&self, user: u64) -> Result<(), anyhow::Error> {
+        diesel::insert_or_ignore_into(crate::schema::StickyMembers::table)
+            .values(&NewStickyMember {
+                user_id: user,
+                timeout_until: None,
+                nickname: None,
+            })
+            .execute(&self.pool.get()?)?;
+        Ok(())
+    }
+
+    pub async fn set_sticky_timeout(
+        &self,
+        user: u64,
+        until: Option<chrono::NaiveDateTime>,
+    ) -> Result<usize, anyhow::Error> {
+        use crate::schema::StickyMembers::dsl::*;
+        self.ensure_sticky_member(user)?;
+        Ok(diesel::update(StickyMembers.filter(user_id.eq(user)))
+            .set(timeout_until.eq(until))
+            .execute(&self.pool.get()?)?)
+    }
+
+    pub async fn set_sticky_nickname(
+        &self,
+        user: u64,
+        nick: Option<&str>,
+    ) -> Result<usize, anyhow::Error> {
+        use crate::schema::StickyMembers::dsl::*;
+        self.ensure_sticky_member(user)?;
+        Ok(diesel::update(StickyMembers.filter(user_id.eq(user)))
+            .set(nickname.eq(nick))
+            .execute(&self.pool.get()?)?)
+    }
+}
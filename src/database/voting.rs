@@ -1,39 +1,403 @@
 use diesel::prelude::*;
-use serenity::model::channel::Message;
+use serenity::model::id::MessageId;
 
 use super::Database;
-use crate::models::*;
+use crate::{
+    models::*,
+    moderation::{
+        council::{CouncilAction, VotingResolution},
+        reputation::ReporterRecord,
+    },
+    voting::{Report, Suspect},
+};
 
 impl Database {
+    /// Stores a new council voting along with the votes required by each action offered on it
     pub async fn new_reported_message(
         &self,
         voting_message_id: u64,
-        message: Message,
-        reporterid: u64,
+        suspect: Suspect,
+        report: Report,
         mods_online: i32,
+        thresholds: Vec<(CouncilAction, i32)>,
+        voting_deadline: chrono::NaiveDateTime,
     ) -> Result<usize, anyhow::Error> {
         let new_voting = NewCouncilVoting {
             vote_message_id: voting_message_id,
-            suspect_id: message.author.id.0,
-            suspect_message_id: message.id.0,
-            suspect_message_channel_id: message.channel_id.0,
-            suspect_message_send_time: message.timestamp.naive_local(),
-            suspect_message_content: message.content,
-            reporter_id: reporterid,
-            delete_votes: 0,
-            delete_votes_required: (mods_online as f32).sqrt().clamp(1., 3.).round() as i32,
-            silence_votes: 0,
-            silence_votes_required: (mods_online as f32).sqrt().round() as i32,
-            block_reporter_votes: 0,
-            block_reporter_votes_required: (mods_online as f32).sqrt().clamp(1., 3.).round() as i32,
+            suspect_id: suspect.user_id,
+            suspect_message_id: suspect.message_id,
+            suspect_message_channel_id: suspect.channel_id,
+            suspect_message_send_time: suspect.send_time,
+            suspect_message_content: suspect.content,
+            reporter_id: report.reporter_id,
             moderators_online: mods_online,
             useless_clicks: 0,
+            deadline: voting_deadline,
+            resolution: VotingResolution::Pending.as_str().to_string(),
+            report_category: report.category.map(|c| c.as_str().to_string()),
+            report_reason: report.reason,
+            reply_to: suspect.reply_to,
+            context: suspect.context,
         };
-        Ok(diesel::insert_into(crate::schema::CouncilVotings::table)
-            .values(&new_voting)
+        let new_thresholds = thresholds
+            .into_iter()
+            .map(|(action, votes_required)| NewCouncilVotingThreshold {
+                vote_message_id: voting_message_id,
+                vote_type: action.id(),
+                votes_required,
+            })
+            .collect::<Vec<_>>();
+        // A voting without its thresholds can't be voted on, so they are stored together
+        let conn = self.pool.get()?;
+        conn.transaction::<_, anyhow::Error, _>(|| {
+            diesel::insert_into(crate::schema::CouncilVotingThresholds::table)
+                .values(&new_thresholds)
+                .execute(&conn)?;
+            Ok(diesel::insert_into(crate::schema::CouncilVotings::table)
+                .values(&new_voting)
+                .execute(&conn)?)
+        })
+    }
+
+    /// Adds a report to an existing voting. Returns 0 if the reporter has already reported the
+    /// message.
+    pub async fn add_report(
+        &self,
+        voting_message_id: u64,
+        report: Report,
+    ) -> Result<usize, anyhow::Error> {
+        let new_report = NewCouncilVotingReport {
+            vote_message_id: voting_message_id,
+            reporter_id: report.reporter_id,
+            category: report.category.map(|c| c.as_str().to_string()),
+            reason: report.reason,
+            reported_at: chrono::Local::now().naive_local(),
+        };
+        Ok(
+            diesel::insert_or_ignore_into(crate::schema::CouncilVotingReports::table)
+                .values(&new_report)
+                .execute(&self.pool.get()?)?,
+        )
+    }
+
+    pub async fn get_reports(
+        &self,
+        voting_message_id: u64,
+    ) -> Result<Vec<CouncilVotingReport>, anyhow::Error> {
+        use crate::schema::CouncilVotingReports::dsl::*;
+        Ok(CouncilVotingReports
+            .filter(vote_message_id.eq(voting_message_id))
+            .order_by(id)
+            .load::<CouncilVotingReport>(&self.pool.get()?)?)
+    }
+
+    /// Everyone who has reported the message of the voting, first reporter first
+    pub async fn get_reporters(&self, voting: &CouncilVoting) -> Result<Vec<u64>, anyhow::Error> {
+        let mut reporters = vec![voting.reporter_id];
+        reporters.extend(
+            self.get_reports(voting.vote_message_id)
+                .await?
+                .into_iter()
+                .map(|r| r.reporter_id),
+        );
+        Ok(reporters)
+    }
+
+    /// The most recent votings the user has reported, either first or as a later reporter
+    pub async fn get_user_reports(
+        &self,
+        user_id: u64,
+        count: i64,
+    ) -> Result<Vec<CouncilVoting>, anyhow::Error> {
+        let conn = self.pool.get()?;
+        let later_reports = {
+            use crate::schema::CouncilVotingReports::dsl::*;
+            CouncilVotingReports
+                .filter(reporter_id.eq(user_id))
+                .select(vote_message_id)
+                .load::<u64>(&conn)?
+        };
+        use crate::schema::CouncilVotings::dsl::*;
+        Ok(CouncilVotings
+            .filter(
+                reporter_id
+                    .eq(user_id)
+                    .or(vote_message_id.eq_any(later_reports)),
+            )
+            .order_by(id.desc())
+            .limit(count)
+            .load::<CouncilVoting>(&conn)?)
+    }
+
+    /// What came of the votings the user has reported. Votings that are still open are left
+    /// out, and only the first reporter can be blocked for a report.
+    pub async fn get_reporter_record(&self, user_id: u64) -> Result<ReporterRecord, anyhow::Error> {
+        let conn = self.pool.get()?;
+        let later_reports = {
+            use crate::schema::CouncilVotingReports::dsl::*;
+            CouncilVotingReports
+                .filter(reporter_id.eq(user_id))
+                .select(vote_message_id)
+                .load::<u64>(&conn)?
+        };
+        let votings = {
+            use crate::schema::CouncilVotings::dsl::*;
+            CouncilVotings
+                .filter(
+                    reporter_id
+                        .eq(user_id)
+                        .or(vote_message_id.eq_any(later_reports)),
+                )
+                .load::<CouncilVoting>(&conn)?
+        };
+        let executed = {
+            use crate::schema::CouncilVotingThresholds::dsl::*;
+            CouncilVotingThresholds
+                .filter(vote_message_id.eq_any(votings.iter().map(|v| v.vote_message_id)))
+                .filter(executed_at.is_not_null())
+                .select((vote_message_id, vote_type))
+                .load::<(u64, i32)>(&conn)?
+        };
+
+        let mut record = ReporterRecord::default();
+        for voting in votings {
+            let executed_here = |blocking: bool| {
+                executed.iter().any(|&(id, action)| {
+                    id == voting.vote_message_id
+                        && (action == CouncilAction::BlockReporter.id()) == blocking
+                })
+            };
+            if executed_here(true) && voting.reporter_id == user_id {
+                record.blocked += 1;
+            } else if executed_here(false) {
+                record.actioned += 1;
+            } else if voting.closed_at.is_some() {
+                record.ignored += 1;
+            }
+        }
+        Ok(record)
+    }
+
+    /// When the user last reported a message, if ever
+    pub async fn get_last_report_time(
+        &self,
+        user_id: u64,
+    ) -> Result<Option<chrono::NaiveDateTime>, anyhow::Error> {
+        let conn = self.pool.get()?;
+        let later_report = {
+            use crate::schema::CouncilVotingReports::dsl::*;
+            CouncilVotingReports
+                .filter(reporter_id.eq(user_id))
+                .order_by(reported_at.desc())
+                .select(reported_at)
+                .first::<chrono::NaiveDateTime>(&conn)
+                .optional()?
+        };
+        let first_report = {
+            use crate::schema::CouncilVotings::dsl::*;
+            CouncilVotings
+                .filter(reporter_id.eq(user_id))
+                .order_by(id.desc())
+                .select(vote_message_id)
+                .first::<u64>(&conn)
+                .optional()?
+        }
+        // The voting message is sent as the message is first reported, so its id tells the time
+        .map(|id| {
+            MessageId(id)
+                .created_at()
+                .with_timezone(&chrono::Local)
+                .naive_local()
+        });
+        Ok(later_report.max(first_report))
+    }
+
+    /// Turns the direct messages about resolved reports on or off for the user
+    pub async fn set_report_notifications(
+        &self,
+        user: u64,
+        enabled: bool,
+    ) -> Result<usize, anyhow::Error> {
+        use crate::schema::ReportNotifications::dsl::*;
+        let conn = self.pool.get()?;
+        if enabled {
+            Ok(diesel::insert_or_ignore_into(ReportNotifications)
+                .values(&NewReportNotification {
+                    user_id: user,
+                    subscribed_at: chrono::Local::now().naive_local(),
+                })
+                .execute(&conn)?)
+        } else {
+            Ok(diesel::delete(ReportNotifications.filter(user_id.eq(user))).execute(&conn)?)
+        }
+    }
+
+    pub async fn has_report_notifications(&self, user: u64) -> Result<bool, anyhow::Error> {
+        use crate::schema::ReportNotifications::dsl::*;
+        Ok(ReportNotifications
+            .filter(user_id.eq(user))
+            .select(user_id)
+            .first::<u64>(&self.pool.get()?)
+            .optional()?
+            .is_some())
+    }
+
+    pub async fn add_report_attachment(
+        &self,
+        attachment: &NewReportAttachment,
+    ) -> Result<usize, anyhow::Error> {
+        Ok(diesel::insert_into(crate::schema::ReportAttachments::table)
+            .values(attachment)
             .execute(&self.pool.get()?)?)
     }
 
+    pub async fn get_report_attachments(
+        &self,
+        voting_message_id: u64,
+    ) -> Result<Vec<ReportAttachment>, anyhow::Error> {
+        use crate::schema::ReportAttachments::dsl::*;
+        Ok(ReportAttachments
+            .filter(vote_message_id.eq(voting_message_id))
+            .order_by(id)
+            .load::<ReportAttachment>(&self.pool.get()?)?)
+    }
+
+    pub async fn get_voting_thresholds(
+        &self,
+        voting_message_id: u64,
+    ) -> Result<Vec<CouncilVotingThreshold>, anyhow::Error> {
+        use crate::schema::CouncilVotingThresholds::dsl::*;
+        Ok(CouncilVotingThresholds
+            .filter(vote_message_id.eq(voting_message_id))
+            .order_by(id)
+            .load::<CouncilVotingThreshold>(&self.pool.get()?)?)
+    }
+
+    /// Marks the action of the voting as carried out, and the voting as actioned. Returns 0 if
+    /// the action already was carried out, in which case it must not be carried out again.
+    pub async fn mark_action_executed(
+        &self,
+        voting_message_id: u64,
+        action: CouncilAction,
+    ) -> Result<usize, anyhow::Error> {
+        use crate::schema::CouncilVotingThresholds::dsl::*;
+        let updated = diesel::update(
+            CouncilVotingThresholds
+                .filter(vote_message_id.eq(voting_message_id))
+                .filter(vote_type.eq(action.id()))
+                .filter(executed_at.is_null()),
+        )
+        .set(executed_at.eq(chrono::Local::now().naive_local()))
+        .execute(&self.pool.get()?)?;
+        if updated > 0 {
+            use crate::schema::CouncilVotings::dsl::*;
+            diesel::update(
+                CouncilVotings
+                    .filter(vote_message_id.eq(voting_message_id))
+                    .filter(resolution.eq(VotingResolution::Pending.as_str())),
+            )
+            .set(resolution.eq(VotingResolution::Actioned.as_str()))
+            .execute(&self.pool.get()?)?;
+        }
+        Ok(updated)
+    }
+
+    /// Stores what came of carrying out the action, to be shown on the voting message
+    pub async fn set_action_result(
+        &self,
+        voting_message_id: u64,
+        action: CouncilAction,
+        action_result: &str,
+    ) -> Result<(), anyhow::Error> {
+        use crate::schema::CouncilVotingThresholds::dsl::*;
+        diesel::update(
+            CouncilVotingThresholds
+                .filter(vote_message_id.eq(voting_message_id))
+                .filter(vote_type.eq(action.id())),
+        )
+        .set(result.eq(action_result))
+        .execute(&self.pool.get()?)?;
+        Ok(())
+    }
+
+    /// Closes the voting, after which it can no longer be voted on. Returns 0 if the voting
+    /// was already closed.
+    pub async fn close_voting(
+        &self,
+        voting_message_id: u64,
+        voting_resolution: VotingResolution,
+        closer_id: Option<u64>,
+    ) -> Result<usize, anyhow::Error> {
+        use crate::schema::CouncilVotings::dsl::*;
+        Ok(diesel::update(
+            CouncilVotings
+                .filter(vote_message_id.eq(voting_message_id))
+                .filter(closed_at.is_null()),
+        )
+        .set((
+            resolution.eq(voting_resolution.as_str()),
+            closed_at.eq(chrono::Local::now().naive_local()),
+            closed_by.eq(closer_id),
+        ))
+        .execute(&self.pool.get()?)?)
+    }
+
+    /// Open votings whose deadline has passed
+    pub async fn get_expired_votings(&self) -> Result<Vec<CouncilVoting>, anyhow::Error> {
+        use crate::schema::CouncilVotings::dsl::*;
+        Ok(CouncilVotings
+            .filter(closed_at.is_null())
+            .filter(deadline.le(chrono::Local::now().naive_local()))
+            .load::<CouncilVoting>(&self.pool.get()?)?)
+    }
+
+    /// The votings whose voting message is newer than the given id, i.e. the votings opened
+    /// since the time the id was created
+    pub async fn get_votings_since(
+        &self,
+        first_message_id: u64,
+    ) -> Result<Vec<CouncilVoting>, anyhow::Error> {
+        use crate::schema::CouncilVotings::dsl::*;
+        Ok(CouncilVotings
+            .filter(vote_message_id.ge(first_message_id))
+            .order_by(id)
+            .load::<CouncilVoting>(&self.pool.get()?)?)
+    }
+
+    /// The later reports, thresholds and votes of the given votings
+    pub async fn get_voting_details(
+        &self,
+        voting_message_ids: &[u64],
+    ) -> Result<
+        (
+            Vec<CouncilVotingReport>,
+            Vec<CouncilVotingThreshold>,
+            Vec<VotingAction>,
+        ),
+        anyhow::Error,
+    > {
+        let conn = self.pool.get()?;
+        let reports = {
+            use crate::schema::CouncilVotingReports::dsl::*;
+            CouncilVotingReports
+                .filter(vote_message_id.eq_any(voting_message_ids))
+                .load::<CouncilVotingReport>(&conn)?
+        };
+        let thresholds = {
+            use crate::schema::CouncilVotingThresholds::dsl::*;
+            CouncilVotingThresholds
+                .filter(vote_message_id.eq_any(voting_message_ids))
+                .load::<CouncilVotingThreshold>(&conn)?
+        };
+        let votes = {
+            use crate::schema::VotingActions::dsl::*;
+            VotingActions
+                .filter(voting_message_id.eq_any(voting_message_ids))
+                .load::<VotingAction>(&conn)?
+        };
+        Ok((reports, thresholds, votes))
+    }
+
     pub async fn get_voting_event(
         &self,
         voting_message_id: u64,
@@ -84,6 +448,19 @@ impl Database {
             .first::<CouncilVoting>(&self.pool.get()?)?)
     }
 
+    /// The most recent council voting about the user, if they have ever been reported
+    pub async fn get_latest_voting_event_for_user(
+        &self,
+        userid: u64,
+    ) -> Result<Option<CouncilVoting>, anyhow::Error> {
+        use crate::schema::CouncilVotings::dsl::*;
+        Ok(CouncilVotings
+            .filter(suspect_id.eq(userid))
+            .order_by(id.desc())
+            .first::<CouncilVoting>(&self.pool.get()?)
+            .optional()?)
+    }
+
     pub async fn add_edit_event(
         &self,
         update_event: serenity::model::event::MessageUpdateEvent,
@@ -121,114 +498,46 @@ impl Database {
         )
     }
 
-    pub async fn add_vote(
+    /// Casts the moderator's vote on the action, or takes it back if they have already voted
+    /// for it. Returns whether the vote was cast and the number of votes the action has now.
+    ///
+    /// The threshold row of the action is locked for the transaction, so that concurrent votes on
+    /// the same action are counted one after another and each of them sees the earlier ones.
+    pub async fn toggle_vote(
         &self,
         voting_message_id: u64,
         voter_user_id: u64,
-        vote_type: i32,
-    ) -> Result<usize, anyhow::Error> {
-        let vote = NewVotingAction {
-            vote_type,
-            voter_user_id,
-            voting_message_id,
-        };
-        {
-            use crate::schema::VotingActions::dsl::*;
-            // FIXME: REALLY Q&D
-            if !VotingActions
-                .filter(
-                    vote_type
-                        .eq(vote.vote_type)
-                        .and(voter_user_id.eq(vote.voter_user_id))
-                        .and(voting_message_id.eq(vote.voting_message_id)),
-                )
-                .load::<VotingAction>(&self.pool.get()?)?
-                .is_empty()
+        action: CouncilAction,
+    ) -> Result<(bool, i64), anyhow::Error> {
+        let conn = self.pool.get()?;
+        conn.transaction::<_, anyhow::Error, _>(|| {
             {
-                return Ok(0);
+                use crate::schema::CouncilVotingThresholds::dsl::*;
+                CouncilVotingThresholds
+                    .filter(vote_message_id.eq(voting_message_id))
+                    .filter(vote_type.eq(action.id()))
+                    .for_update()
+                    .first::<CouncilVotingThreshold>(&conn)?;
             }
-        }
-
-        diesel::insert_into(crate::schema::VotingActions::table)
-            .values(&vote)
-            .execute(&self.pool.get()?)?;
-
-        use crate::schema::CouncilVotings::dsl::*;
-        match vote_type {
-            0 => Ok(
-                diesel::update(CouncilVotings.filter(vote_message_id.eq(voting_message_id)))
-                    .set(delete_votes.eq(delete_votes + 1))
-                    .execute(&self.pool.get()?)?,
-            ),
-            1 => Ok(
-                diesel::update(CouncilVotings.filter(vote_message_id.eq(voting_message_id)))
-                    .set(silence_votes.eq(silence_votes + 1))
-                    .execute(&self.pool.get()?)?,
-            ),
-            2 => Ok(
-                diesel::update(CouncilVotings.filter(vote_message_id.eq(voting_message_id)))
-                    .set(block_reporter_votes.eq(block_reporter_votes + 1))
-                    .execute(&self.pool.get()?)?,
-            ),
-            _ => Ok(0),
-        }
-    }
-
-    pub async fn remove_vote(
-        &self,
-        voting_message_id: u64,
-        voter_user_id: u64,
-        vote_type: i32,
-    ) -> Result<usize, anyhow::Error> {
-        let vote = NewVotingAction {
-            vote_type,
-            voter_user_id,
-            voting_message_id,
-        };
-        {
-            use crate::schema::VotingActions::dsl::*;
-            // FIXME: REALLY Q&D
-            if VotingActions
-                .filter(
-                    vote_type
-                        .eq(vote.vote_type)
-                        .and(voter_user_id.eq(vote.voter_user_id))
-                        .and(voting_message_id.eq(vote.voting_message_id)),
-                )
-                .load::<VotingAction>(&self.pool.get()?)?
-                .is_empty()
-            {
-                return Ok(0);
+            use crate::schema::VotingActions::dsl;
+            let vote = dsl::VotingActions
+                .filter(dsl::voting_message_id.eq(voting_message_id))
+                .filter(dsl::vote_type.eq(action.id()));
+            let removed =
+                diesel::delete(vote.filter(dsl::voter_user_id.eq(voter_user_id))).execute(&conn)?;
+            if removed == 0 {
+                // The unique index keeps a double click from counting twice
+                diesel::insert_or_ignore_into(dsl::VotingActions)
+                    .values(&NewVotingAction {
+                        vote_type: action.id(),
+                        voter_user_id,
+                        voting_message_id,
+                    })
+                    .execute(&conn)?;
             }
-            diesel::delete(crate::schema::VotingActions::table)
-                .filter(
-                    vote_type
-                        .eq(vote.vote_type)
-                        .and(voter_user_id.eq(vote.voter_user_id))
-                        .and(voting_message_id.eq(vote.voting_message_id)),
-                )
-                .execute(&self.pool.get()?)?;
-        }
-
-        use crate::schema::CouncilVotings::dsl::*;
-        match vote_type {
-            0 => Ok(
-                diesel::update(CouncilVotings.filter(vote_message_id.eq(voting_message_id)))
-                    .set(delete_votes.eq(delete_votes - 1))
-                    .execute(&self.pool.get()?)?,
-            ),
-            1 => Ok(
-                diesel::update(CouncilVotings.filter(vote_message_id.eq(voting_message_id)))
-                    .set(silence_votes.eq(silence_votes - 1))
-                    .execute(&self.pool.get()?)?,
-            ),
-            2 => Ok(
-                diesel::update(CouncilVotings.filter(vote_message_id.eq(voting_message_id)))
-                    .set(block_reporter_votes.eq(block_reporter_votes - 1))
-                    .execute(&self.pool.get()?)?,
-            ),
-            _ => Ok(0),
-        }
+            let count = vote.count().get_result::<i64>(&conn)?;
+            Ok((removed == 0, count))
+        })
     }
 
     pub async fn add_useless_click(&self, message_id: u64) -> Result<usize, anyhow::Error> {
@@ -250,13 +559,42 @@ impl Database {
             .is_some())
     }
 
-    pub async fn silence_user(&self, userid: u64) -> Result<usize, anyhow::Error> {
-        let new_silence = NewSilencedMember { user_id: userid };
-        Ok(diesel::insert_into(crate::schema::SilencedMembers::table)
+    /// Stores the silence, replacing any earlier one. A silence without `expires_at` lasts
+    /// until it is lifted by hand.
+    pub async fn silence_user(
+        &self,
+        userid: u64,
+        issuer_id: Option<u64>,
+        reason: Option<String>,
+        expires_at: Option<chrono::NaiveDateTime>,
+    ) -> Result<usize, anyhow::Error> {
+        let new_silence = NewSilencedMember {
+            user_id: userid,
+            issuer_id,
+            reason,
+            silenced_at: Some(chrono::Local::now().naive_local()),
+            expires_at,
+        };
+        Ok(diesel::replace_into(crate::schema::SilencedMembers::table)
             .values(&new_silence)
             .execute(&self.pool.get()?)?)
     }
 
+    pub async fn get_silence(&self, userid: u64) -> Result<Option<SilencedMember>, anyhow::Error> {
+        use crate::schema::SilencedMembers::dsl::*;
+        Ok(SilencedMembers
+            .filter(user_id.eq(userid))
+            .first::<SilencedMember>(&self.pool.get()?)
+            .optional()?)
+    }
+
+    pub async fn get_expired_silences(&self) -> Result<Vec<SilencedMember>, anyhow::Error> {
+        use crate::schema::SilencedMembers::dsl::*;
+        Ok(SilencedMembers
+            .filter(expires_at.le(chrono::Local::now().naive_local()))
+            .load::<SilencedMember>(&self.pool.get()?)?)
+    }
+
     pub async fn unsilence_user(&self, userid: u64) -> Result<usize, anyhow::Error> {
         use crate::schema::SilencedMembers::dsl::*;
         Ok(
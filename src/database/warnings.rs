@@ -0,0 +1,43 @@
+use diesel::prelude::*;
+
+use super::Database;
+use crate::models::*;
+
+impl Database {
+    pub async fn add_warning(
+        &self,
+        user_id: u64,
+        issuer_id: u64,
+        reason: &str,
+        expires_at: chrono::NaiveDateTime,
+    ) -> Result<usize, anyhow::Error> {
+        let warning = NewWarning {
+            user_id,
+            issuer_id,
+            reason: reason.to_string(),
+            issued_at: chrono::Local::now().naive_local(),
+            expires_at,
+        };
+        Ok(diesel::insert_into(crate::schema::Warnings::table)
+            .values(&warning)
+            .execute(&self.pool.get()?)?)
+    }
+
+    /// All the warnings of the user, newest first
+    pub async fn get_warnings(&self, target_id: u64) -> Result<Vec<Warning>, anyhow::Error> {
+        use crate::schema::Warnings::dsl::*;
+        Ok(Warnings
+            .filter(user_id.eq(target_id))
+            .order_by(id.desc())
+            .load::<Warning>(&self.pool.get()?)?)
+    }
+
+    pub async fn count_active_warnings(&self, target_id: u64) -> Result<i64, anyhow::Error> {
+        use crate::schema::Warnings::dsl::*;
+        Ok(Warnings
+            .filter(user_id.eq(target_id))
+            .filter(expires_at.gt(chrono::Local::now().naive_local()))
+            .count()
+            .get_result::<i64>(&self.pool.get()?)?)
+    }
+}
@@ -1,20 +1,17 @@
-use std::{env, io::Cursor, sync::Arc};
+use std::{io::Cursor, sync::Arc};
 
 use futures::prelude::*;
-use serenity::{http::client::Http, model::id::ChannelId};
+use serenity::{
+    http::client::Http,
+    model::id::ChannelId,
+    prelude::{RwLock, TypeMap},
+};
 use tracing::error;
 
-use crate::database::Database;
-async fn give_award_role(http: &Http, db: Arc<Database>, winner: u64) {
-    let award_role_id: u64 = env::var("AWARD_ROLE_ID")
-        .expect("No AWARD_ROLE_ID in .env")
-        .parse()
-        .expect("Invalid AWARD_ROLE_ID");
-
-    let guild_id: u64 = env::var("GUILD_ID")
-        .expect("Expected GUILD_ID in .env")
-        .parse()
-        .expect("Invalid GUILD_ID provided");
+use crate::{config::Config, database::Database, extensions::*};
+async fn give_award_role(http: &Http, db: Arc<Database>, config: &Config, winner: u64) {
+    let award_role_id = config.award_role_id;
+    let guild_id = config.guild_id;
 
     if let Ok(previous_winner) = db.get_last_winner().await {
         if let Ok(mut member) = http.get_member(guild_id, previous_winner).await {
@@ -30,18 +27,18 @@ async fn give_award_role(http: &Http, db: Arc<Database>, winner: u64) {
     db.new_winner(winner).await.ok();
 }
 
-pub async fn display_winner(http: Arc<Http>, db: Arc<Database>, offset: i32) {
-    let db = db;
-    let winners = db.get_most_active(5, offset).await.unwrap();
+pub async fn display_winner(http: Arc<Http>, data: Arc<RwLock<TypeMap>>, offset: i32) {
+    let db = data.get_db().await;
+    let config = data.get_config().await;
+    let award_blacklist = data.get_award_blacklist().await;
+    let winners = db
+        .get_most_active(5, offset, &award_blacklist.0)
+        .await
+        .unwrap();
     let total_msgs = db.get_total_daily_messages(offset).await.unwrap();
     let messages_average = db.get_total_message_average(offset).await.unwrap();
 
-    let channel = ChannelId::from(
-        env::var("AWARD_CHANNEL_ID")
-            .unwrap()
-            .parse::<u64>()
-            .unwrap(),
-    );
+    let channel = ChannelId::from(config.award_channel_id);
 
     let guild_id = channel
         .to_channel(&http)
@@ -64,7 +61,13 @@ pub async fn display_winner(http: Arc<Http>, db: Arc<Database>, offset: i32) {
         Ok(winner) => {
             let img_name = build_award_image(&winner.face()).await;
 
-            give_award_role(&http, db.clone(), winners[0].0.as_ref().unwrap().user.id.0).await;
+            give_award_role(
+                &http,
+                db.clone(),
+                &config,
+                winners[0].0.as_ref().unwrap().user.id.0,
+            )
+            .await;
 
             channel
                 .send_message(&http, |m| {
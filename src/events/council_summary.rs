@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use serenity::{
+    http::Http,
+    model::id::ChannelId,
+    prelude::{RwLock, TypeMap},
+};
+
+use crate::{extensions::*, moderation::council_stats::CouncilStats};
+
+/// Posts the statistics of the past week's council votings to the moderation channel
+pub async fn post_weekly_summary(http: Arc<Http>, data: Arc<RwLock<TypeMap>>) {
+    let config = data.get_config().await;
+    let db = data.get_db().await;
+    let since = chrono::Utc::now() - chrono::Duration::weeks(1);
+    let stats = match CouncilStats::collect(&db, since).await {
+        Ok(stats) => stats,
+        Err(e) => {
+            error!("Unable to collect council statistics: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = ChannelId(config.mod_channel_id)
+        .send_message(&http, |m| {
+            m.embed(|e| {
+                e.title("Neuvoston viikkokatsaus");
+                stats.write_embed(e, config.guild_id, config.mod_channel_id);
+                e
+            })
+        })
+        .await
+    {
+        error!("Unable to post the weekly council summary: {}", e);
+    }
+}
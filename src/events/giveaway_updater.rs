@@ -4,12 +4,8 @@ use chrono::{DateTime, Utc};
 
 use crate::{commands::giveaway::end_giveaway, database::Database, Http, ReactionType};
 
-pub async fn update_giveaways(http: Arc<Http>, db: impl AsRef<Database>) {
+pub async fn update_giveaways(http: Arc<Http>, db: impl AsRef<Database>, reaction_emoji: char) {
     let db = db.as_ref();
-    let reaction_emoji: char = std::env::var("GIVEAWAY_REACTION_EMOJI")
-        .unwrap_or("🎉".to_string())
-        .parse()
-        .expect("GIVEAWAY_REACTION_EMOJI is not a valid emoji");
     for g in db.get_ongoing_giveaways().await.unwrap().iter() {
         let end = DateTime::<Utc>::from_utc(g.end_time, Utc);
         let now = Utc::now();
@@ -1,46 +1,95 @@
 pub mod activity_award;
+pub mod council_summary;
 pub mod giveaway_updater;
+pub mod silence_expiry;
+pub mod vote_expiry;
 
 use std::sync::Arc;
 
-use clokwerk::{AsyncScheduler, Job, TimeUnits};
-use serenity::http::Http;
+use clokwerk::{AsyncScheduler, Interval, Job, TimeUnits};
+use serenity::{
+    http::Http,
+    prelude::{RwLock, TypeMap},
+};
 
 use crate::{
-    database::Database,
-    events::{activity_award::display_winner, giveaway_updater::update_giveaways},
+    events::{
+        activity_award::display_winner, council_summary::post_weekly_summary,
+        giveaway_updater::update_giveaways, silence_expiry::lift_expired_silences,
+        vote_expiry::close_expired_votings,
+    },
+    extensions::*,
 };
 
-pub fn setup_schedulers(scheduler: &mut AsyncScheduler, http: Arc<Http>, db: Arc<Database>) {
+pub fn setup_schedulers(
+    scheduler: &mut AsyncScheduler,
+    http: Arc<Http>,
+    data: Arc<RwLock<TypeMap>>,
+) {
     {
         let http_clone = http.clone();
-        let db_clone = db.clone();
+        let data_clone = data.clone();
         scheduler.every(1.day()).at("00:00").run(move || {
             let inner_http_clone = http_clone.clone();
-            let inner_db_clone = db_clone.clone();
+            let inner_data_clone = data_clone.clone();
             async move {
-                display_winner(inner_http_clone, inner_db_clone, 1).await;
+                display_winner(inner_http_clone, inner_data_clone, 1).await;
             }
         });
     }
     {
         let http_clone = http.clone();
-        let db_clone = db.clone();
+        let data_clone = data.clone();
         scheduler.every(30.seconds()).run(move || {
             let inner_http_clone = http_clone.clone();
-            let inner_db_clone = db_clone.clone();
+            let inner_data_clone = data_clone.clone();
+            async move {
+                let db = inner_data_clone.get_db().await;
+                let reaction_emoji = inner_data_clone.get_config().await.giveaway_reaction_emoji;
+                update_giveaways(inner_http_clone, db, reaction_emoji).await;
+            }
+        });
+    }
+    {
+        let http_clone = http.clone();
+        let data_clone = data.clone();
+        scheduler.every(1.minute()).run(move || {
+            let inner_http_clone = http_clone.clone();
+            let inner_data_clone = data_clone.clone();
+            async move {
+                lift_expired_silences(inner_http_clone, inner_data_clone).await;
+            }
+        });
+    }
+    {
+        let http_clone = http.clone();
+        let data_clone = data.clone();
+        scheduler.every(5.minutes()).run(move || {
+            let inner_http_clone = http_clone.clone();
+            let inner_data_clone = data_clone.clone();
+            async move {
+                close_expired_votings(inner_http_clone, inner_data_clone).await;
+            }
+        });
+    }
+    {
+        let http_clone = http.clone();
+        let data_clone = data.clone();
+        scheduler.every(Interval::Monday).at("09:00").run(move || {
+            let inner_http_clone = http_clone.clone();
+            let inner_data_clone = data_clone.clone();
             async move {
-                update_giveaways(inner_http_clone, inner_db_clone).await;
+                post_weekly_summary(inner_http_clone, inner_data_clone).await;
             }
         });
     }
     {
         scheduler.every(10.seconds()).run(move || {
             let inner_http_clone = http.clone();
-            let inner_db_clone = db.clone();
+            let inner_data_clone = data.clone();
             async move {
-                if let Err(e) =
-                    crate::commands::vote::update_all_votes(inner_http_clone, inner_db_clone).await
+                let db = inner_data_clone.get_db().await;
+                if let Err(e) = crate::commands::vote::update_all_votes(inner_http_clone, db).await
                 {
                     error!("Error while updating votes: {}", e);
                 }
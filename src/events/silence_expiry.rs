@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use serenity::{
+    http::Http,
+    model::id::UserId,
+    prelude::{RwLock, TypeMap},
+};
+
+use crate::{
+    extensions::*,
+    moderation::{
+        action::{error_code, UNKNOWN_MEMBER},
+        log_action, ActionKind, ActionSource,
+    },
+};
+
+/// Lifts the silences whose time has run out
+pub async fn lift_expired_silences(http: Arc<Http>, data: Arc<RwLock<TypeMap>>) {
+    let db = data.get_db().await;
+    let config = data.get_config().await;
+    let expired = match db.get_expired_silences().await {
+        Ok(s) => s,
+        Err(e) => {
+            error!("Unable to fetch expired silences: {}", e);
+            return;
+        }
+    };
+
+    for silence in expired {
+        info!("Silence of {} has expired, lifting", silence.user_id);
+        // The role is removed first, so that a failure leaves the silence to be tried again on
+        // the next run. `guild_member_update` leaves expired silences for this job to log.
+        // A member who has left has no role to remove, and the silence simply isn't reapplied
+        // when they come back.
+        match http
+            .remove_member_role(
+                config.guild_id,
+                silence.user_id,
+                config.silenced_role_id,
+                Some("Hiljennys päättyi"),
+            )
+            .await
+        {
+            Err(e) if error_code(&e) != Some(UNKNOWN_MEMBER) => {
+                error!(
+                    "Unable to remove the silenced role from {}, trying again later: {}",
+                    silence.user_id, e
+                );
+                continue;
+            }
+            _ => {}
+        }
+        if let Err(e) = db.unsilence_user(silence.user_id).await {
+            error!("Unable to unsilence {}: {}", silence.user_id, e);
+            continue;
+        }
+        log_action(
+            &*data,
+            silence.user_id,
+            None,
+            ActionKind::Unsilence,
+            ActionSource::Expiry,
+            silence.reason,
+            None,
+        )
+        .await;
+
+        let dm = match UserId(silence.user_id).create_dm_channel(&http).await {
+            Ok(channel) => {
+                channel
+                    .send_message(&http, |m| {
+                        m.content(format!(
+                            "Hiljennyksesi on päättynyt ja voit taas keskustella palvelimella. Tarkistathan sääntömme kanavalta <#{}>.",
+                            config.rules_channel_id
+                        ))
+                    })
+                    .await
+            }
+            Err(e) => Err(e),
+        };
+        if dm.is_err() {
+            info!(
+                "Unable to send \"Unsilenced notification\" to {}",
+                silence.user_id
+            );
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use serenity::{
+    http::Http,
+    prelude::{RwLock, TypeMap},
+};
+
+use crate::{extensions::*, moderation::council::VotingResolution, voting::refresh_voting_message};
+
+/// Closes the council votings whose deadline has passed. Votings on which nothing was done are
+/// marked expired, while actioned ones keep their resolution.
+pub async fn close_expired_votings(http: Arc<Http>, data: Arc<RwLock<TypeMap>>) {
+    let db = data.get_db().await;
+    let expired = match db.get_expired_votings().await {
+        Ok(v) => v,
+        Err(e) => {
+            error!("Unable to fetch expired votings: {}", e);
+            return;
+        }
+    };
+
+    for voting in expired {
+        let resolution = match voting.resolution.parse() {
+            Ok(VotingResolution::Pending) | Err(_) => VotingResolution::Expired,
+            Ok(resolution) => resolution,
+        };
+        info!(
+            "Council voting {} has expired, closing as {}",
+            voting.vote_message_id,
+            resolution.as_str()
+        );
+        if let Err(e) = db
+            .close_voting(voting.vote_message_id, resolution, None)
+            .await
+        {
+            error!("Unable to close voting {}: {}", voting.vote_message_id, e);
+            continue;
+        }
+        if let Err(e) = refresh_voting_message(&http, &*data, voting.vote_message_id).await {
+            info!(
+                "Unable to update the message of voting {}: {}",
+                voting.vote_message_id, e
+            );
+        }
+    }
+}
@@ -0,0 +1,326 @@
+//! Evidence for council votings that would otherwise be lost with the reported message: its
+//! attachments, embeds and stickers, and the messages around it
+
+use std::{borrow::Cow, fmt::Write, path::PathBuf};
+
+use serenity::model::{
+    channel::{Attachment, AttachmentType},
+    id::ChannelId,
+};
+
+use crate::{
+    blacklist::embed_text,
+    extensions::*,
+    models::{NewReportAttachment, SuspectMessageEdit},
+    Context, Message,
+};
+
+/// The longest excerpt of a single message shown as context
+const CONTEXT_MESSAGE_LENGTH: usize = 200;
+
+/// The text of a message along with its embeds and stickers, which have no content of their own
+pub fn message_text(message: &Message) -> String {
+    let mut parts = vec![message.content.clone()];
+    parts.extend(
+        message
+            .embeds
+            .iter()
+            .map(|e| format!("[Upote]\n{}", embed_text(e))),
+    );
+    parts.extend(
+        message
+            .sticker_items
+            .iter()
+            .map(|s| format!("[Tarra: {}]", s.name)),
+    );
+    parts.retain(|p| !p.is_empty());
+    parts.join("\n")
+}
+
+fn excerpt(message: &Message) -> String {
+    let text = message_text(message);
+    let mut excerpt = text
+        .chars()
+        .take(CONTEXT_MESSAGE_LENGTH)
+        .collect::<String>();
+    if excerpt.len() < text.len() {
+        excerpt.push('…');
+    }
+    format!("{}: {}", message.author.tag(), excerpt)
+}
+
+/// The message the reported message replied to, if any
+pub fn reply_text(message: &Message) -> Option<String> {
+    message.referenced_message.as_deref().map(excerpt)
+}
+
+/// The `REPORT_CONTEXT_MESSAGES` messages sent to the channel before the reported one, oldest
+/// first
+pub async fn fetch_context(ctx: &Context, channel_id: u64, message_id: u64) -> Option<String> {
+    let limit = ctx.get_config().await.report_context_messages;
+    if limit == 0 {
+        return None;
+    }
+    let messages = match ChannelId(channel_id)
+        .messages(&ctx.http, |r| r.before(message_id).limit(limit))
+        .await
+    {
+        Ok(messages) => messages,
+        Err(e) => {
+            info!(
+                "Unable to fetch the context of message {}: {}",
+                message_id, e
+            );
+            return None;
+        }
+    };
+    if messages.is_empty() {
+        return None;
+    }
+    Some(
+        messages
+            .iter()
+            .rev()
+            .map(excerpt)
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+/// Saves the attachments of a reported message under `REPORT_ARCHIVE_DIR` and uploads them as a
+/// reply to the voting message, so that they survive the deletion of the reported message
+pub async fn archive_attachments(
+    ctx: &Context,
+    voting_message: &Message,
+    attachments: Vec<Attachment>,
+) {
+    if attachments.is_empty() {
+        return;
+    }
+    let db = ctx.get_db().await;
+    let config = ctx.get_config().await;
+    let dir = PathBuf::from(&config.report_archive_dir).join(voting_message.id.0.to_string());
+    if let Err(e) = tokio::fs::create_dir_all(&dir).await {
+        error!(
+            "Unable to create the report archive {}: {}",
+            dir.display(),
+            e
+        );
+    }
+
+    let mut archived = Vec::new();
+    for attachment in attachments {
+        // The id keeps attachments with the same name apart
+        let filename = format!(
+            "{}_{}",
+            attachment.id.0,
+            attachment.filename.replace(['/', '\\'], "_")
+        );
+        // The attachment is read into memory whole, so large ones are left at their URL
+        let data = if attachment.size > config.report_attachment_max_bytes {
+            info!(
+                "Not archiving attachment {} of {} bytes",
+                attachment.url, attachment.size
+            );
+            None
+        } else {
+            match attachment.download().await {
+                Ok(data) => Some(data),
+                Err(e) => {
+                    info!("Unable to download attachment {}: {}", attachment.url, e);
+                    None
+                }
+            }
+        };
+        let mut archive_path = None;
+        if let Some(data) = &data {
+            let path = dir.join(&filename);
+            match tokio::fs::write(&path, data).await {
+                Ok(_) => archive_path = Some(path.display().to_string()),
+                Err(e) => error!("Unable to archive attachment {}: {}", path.display(), e),
+            }
+        }
+        archived.push((attachment, filename, data, archive_path));
+    }
+
+    let files = archived
+        .iter()
+        .filter_map(|(_, filename, data, _)| {
+            data.as_ref().map(|data| AttachmentType::Bytes {
+                data: Cow::from(data.as_slice()),
+                filename: filename.clone(),
+            })
+        })
+        .collect::<Vec<_>>();
+    let evidence = if files.is_empty() {
+        None
+    } else {
+        match voting_message
+            .channel_id
+            .send_files(&ctx.http, files, |m| {
+                m.content("Ilmiannetun viestin liitteet")
+                    .reference_message(voting_message)
+            })
+            .await
+        {
+            Ok(message) => Some(message),
+            Err(e) => {
+                // Most likely the files are over the upload limit
+                info!("Unable to upload the attachments of a report: {}", e);
+                None
+            }
+        }
+    };
+
+    for (attachment, filename, _, archive_path) in archived {
+        let evidence_url = evidence.as_ref().and_then(|m| {
+            m.attachments
+                .iter()
+                .find(|a| a.filename == filename)
+                .map(|a| a.url.clone())
+        });
+        let new_attachment = NewReportAttachment {
+            vote_message_id: voting_message.id.0,
+            filename: attachment.filename,
+            url: attachment.url,
+            archive_path,
+            evidence_url,
+        };
+        if let Err(e) = db.add_report_attachment(&new_attachment).await {
+            error!("Unable to store a report attachment: {}", e);
+        }
+    }
+}
+
+/// A line diff of `new` against `old`. Unchanged lines are prefixed with two spaces, removed
+/// lines with `-` and added lines with `+`.
+fn line_diff(old: &str, new: &str) -> String {
+    let old = old.lines().collect::<Vec<_>>();
+    let new = new.lines().collect::<Vec<_>>();
+    // The lengths of the longest common subsequences of every pair of suffixes
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut diff = Vec::new();
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(format!("  {}", old[i]));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            diff.push(format!("- {}", old[i]));
+            i += 1;
+        } else {
+            diff.push(format!("+ {}", new[j]));
+            j += 1;
+        }
+    }
+    diff.extend(old[i..].iter().map(|l| format!("- {}", l)));
+    diff.extend(new[j..].iter().map(|l| format!("+ {}", l)));
+    diff.join("\n")
+}
+
+/// The full edit history of a reported message as text, each edit diffed against the previous
+/// version
+pub fn edit_history(
+    original: &str,
+    send_time: chrono::NaiveDateTime,
+    edits: &[SuspectMessageEdit],
+) -> String {
+    let mut history = String::new();
+    writeln!(
+        history,
+        "Alkuperäinen viesti ({}):\n{}",
+        send_time, original
+    )
+    .unwrap();
+    let mut previous = original;
+    for (n, edit) in edits.iter().enumerate() {
+        if edit.new_content.is_empty() {
+            writeln!(history, "\nViesti poistettu ({})", edit.edit_time).unwrap();
+            break;
+        }
+        writeln!(
+            history,
+            "\nMuokkaus {} ({}):\n{}",
+            n + 1,
+            edit.edit_time,
+            line_diff(previous, &edit.new_content)
+        )
+        .unwrap();
+        previous = &edit.new_content;
+    }
+    history
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time(minute: u32) -> chrono::NaiveDateTime {
+        chrono::NaiveDate::from_ymd_opt(2026, 10, 18)
+            .and_then(|d| d.and_hms_opt(12, minute, 0))
+            .unwrap()
+    }
+
+    fn edit(new_content: &str, minute: u32) -> SuspectMessageEdit {
+        SuspectMessageEdit {
+            id: 0,
+            voting_message_id: 0,
+            suspect_message_id: 0,
+            new_content: new_content.to_string(),
+            edit_time: time(minute),
+        }
+    }
+
+    #[test]
+    fn diffs_unchanged_lines() {
+        assert_eq!(line_diff("a\nb", "a\nb"), "  a\n  b");
+    }
+
+    #[test]
+    fn diffs_changed_lines() {
+        assert_eq!(line_diff("a\nb\nc", "a\nx\nc"), "  a\n- b\n+ x\n  c");
+    }
+
+    #[test]
+    fn diffs_added_and_removed_lines() {
+        assert_eq!(line_diff("a", "a\nb"), "  a\n+ b");
+        assert_eq!(line_diff("a\nb", "b"), "- a\n  b");
+        assert_eq!(line_diff("", "a"), "+ a");
+        assert_eq!(line_diff("a", ""), "- a");
+    }
+
+    #[test]
+    fn diffs_each_edit_against_the_previous_one() {
+        let history = edit_history(
+            "hei",
+            time(0),
+            &[edit("hei\nkaikki", 1), edit("moi\nkaikki", 2)],
+        );
+        assert_eq!(
+            history,
+            "Alkuperäinen viesti (2026-10-18 12:00:00):\nhei\n\
+             \nMuokkaus 1 (2026-10-18 12:01:00):\n  hei\n+ kaikki\n\
+             \nMuokkaus 2 (2026-10-18 12:02:00):\n- hei\n+ moi\n  kaikki\n"
+        );
+    }
+
+    #[test]
+    fn ends_the_history_at_the_deletion() {
+        let history = edit_history("hei", time(0), &[edit("", 1), edit("moi", 2)]);
+        assert_eq!(
+            history,
+            "Alkuperäinen viesti (2026-10-18 12:00:00):\nhei\n\
+             \nViesti poistettu (2026-10-18 12:01:00)\n"
+        );
+    }
+}
@@ -3,16 +3,29 @@ use serenity::{
     model::interactions::application_command::{
         ApplicationCommandInteractionDataOption, ApplicationCommandInteractionDataOptionValue,
     },
+    prelude::{RwLock, TypeMap},
 };
 
 use crate::{
-    database::Database, Arc, Mutex, PartialChannel, PartialMember, PendingEdits, Role, User,
+    blacklist::Blacklist,
+    config::Config,
+    database::Database,
+    file_watcher::{AwardBlacklist, PhishingDomains, SelfServiceRoles},
+    moderation::{antispam::RecentMessages, ratelimit::RateLimiter},
+    Arc, Mutex, PartialChannel, PartialMember, PendingEdits, Role, User,
 };
 
 #[async_trait]
 pub trait ClientContextExt {
     async fn get_db(&self) -> Arc<Database>;
+    async fn get_config(&self) -> Arc<Config>;
     async fn get_pending_edits(&self) -> Arc<Mutex<PendingEdits>>;
+    async fn get_blacklist(&self) -> Arc<Blacklist>;
+    async fn get_self_service_roles(&self) -> Arc<SelfServiceRoles>;
+    async fn get_award_blacklist(&self) -> Arc<AwardBlacklist>;
+    async fn get_phishing_domains(&self) -> Arc<PhishingDomains>;
+    async fn get_recent_messages(&self) -> Arc<Mutex<RecentMessages>>;
+    async fn get_rate_limiter(&self) -> Arc<Mutex<RateLimiter>>;
 }
 
 pub trait InteractionDataOptionExt {
@@ -29,29 +42,120 @@ pub trait ApplicationCommandInteractionDataOptionVecExt {
     fn by_name(&self, name: &str) -> Option<&ApplicationCommandInteractionDataOption>;
 }
 
+#[async_trait]
+impl ClientContextExt for RwLock<TypeMap> {
+    async fn get_db(&self) -> Arc<Database> {
+        self.read().await.get::<Database>().unwrap().clone()
+    }
+
+    async fn get_config(&self) -> Arc<Config> {
+        self.read().await.get::<Config>().unwrap().clone()
+    }
+
+    async fn get_pending_edits(&self) -> Arc<Mutex<PendingEdits>> {
+        self.read().await.get::<PendingEdits>().unwrap().clone()
+    }
+
+    async fn get_blacklist(&self) -> Arc<Blacklist> {
+        self.read().await.get::<Blacklist>().unwrap().clone()
+    }
+
+    async fn get_self_service_roles(&self) -> Arc<SelfServiceRoles> {
+        self.read().await.get::<SelfServiceRoles>().unwrap().clone()
+    }
+
+    async fn get_award_blacklist(&self) -> Arc<AwardBlacklist> {
+        self.read().await.get::<AwardBlacklist>().unwrap().clone()
+    }
+
+    async fn get_phishing_domains(&self) -> Arc<PhishingDomains> {
+        self.read().await.get::<PhishingDomains>().unwrap().clone()
+    }
+
+    async fn get_recent_messages(&self) -> Arc<Mutex<RecentMessages>> {
+        self.read().await.get::<RecentMessages>().unwrap().clone()
+    }
+
+    async fn get_rate_limiter(&self) -> Arc<Mutex<RateLimiter>> {
+        self.read().await.get::<RateLimiter>().unwrap().clone()
+    }
+}
+
 #[async_trait]
 impl ClientContextExt for client::Context {
     async fn get_db(&self) -> Arc<Database> {
-        self.data.read().await.get::<Database>().unwrap().clone()
+        self.data.get_db().await
+    }
+
+    async fn get_config(&self) -> Arc<Config> {
+        self.data.get_config().await
     }
 
     async fn get_pending_edits(&self) -> Arc<Mutex<PendingEdits>> {
-        let data = self.data.read().await;
-        let pending_edits = data.get::<PendingEdits>().unwrap();
-        pending_edits.to_owned()
+        self.data.get_pending_edits().await
+    }
+
+    async fn get_blacklist(&self) -> Arc<Blacklist> {
+        self.data.get_blacklist().await
+    }
+
+    async fn get_self_service_roles(&self) -> Arc<SelfServiceRoles> {
+        self.data.get_self_service_roles().await
+    }
+
+    async fn get_award_blacklist(&self) -> Arc<AwardBlacklist> {
+        self.data.get_award_blacklist().await
+    }
+
+    async fn get_phishing_domains(&self) -> Arc<PhishingDomains> {
+        self.data.get_phishing_domains().await
+    }
+
+    async fn get_recent_messages(&self) -> Arc<Mutex<RecentMessages>> {
+        self.data.get_recent_messages().await
+    }
+
+    async fn get_rate_limiter(&self) -> Arc<Mutex<RateLimiter>> {
+        self.data.get_rate_limiter().await
     }
 }
 
 #[async_trait]
 impl ClientContextExt for client::Client {
     async fn get_db(&self) -> Arc<Database> {
-        self.data.read().await.get::<Database>().unwrap().clone()
+        self.data.get_db().await
+    }
+
+    async fn get_config(&self) -> Arc<Config> {
+        self.data.get_config().await
     }
 
     async fn get_pending_edits(&self) -> Arc<Mutex<PendingEdits>> {
-        let data = self.data.read().await;
-        let pending_edits = data.get::<PendingEdits>().unwrap();
-        pending_edits.to_owned()
+        self.data.get_pending_edits().await
+    }
+
+    async fn get_blacklist(&self) -> Arc<Blacklist> {
+        self.data.get_blacklist().await
+    }
+
+    async fn get_self_service_roles(&self) -> Arc<SelfServiceRoles> {
+        self.data.get_self_service_roles().await
+    }
+
+    async fn get_award_blacklist(&self) -> Arc<AwardBlacklist> {
+        self.data.get_award_blacklist().await
+    }
+
+    async fn get_phishing_domains(&self) -> Arc<PhishingDomains> {
+        self.data.get_phishing_domains().await
+    }
+
+    async fn get_recent_messages(&self) -> Arc<Mutex<RecentMessages>> {
+        self.data.get_recent_messages().await
+    }
+
+    async fn get_rate_limiter(&self) -> Arc<Mutex<RateLimiter>> {
+        self.data.get_rate_limiter().await
     }
 }
 
@@ -127,9 +231,7 @@ impl<'a> InteractionDataOptionExt for &'a ApplicationCommandInteractionDataOptio
     }
 }
 
-impl ApplicationCommandInteractionDataOptionVecExt
-    for Vec<ApplicationCommandInteractionDataOption>
-{
+impl ApplicationCommandInteractionDataOptionVecExt for [ApplicationCommandInteractionDataOption] {
     fn by_name(&self, name: &str) -> Option<&ApplicationCommandInteractionDataOption> {
         self.iter().find(|x| x.name == name)
     }
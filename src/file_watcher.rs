@@ -0,0 +1,174 @@
+use std::{collections::HashSet, marker::PhantomData, sync::Arc, time::SystemTime};
+
+use serenity::prelude::{RwLock, TypeMap, TypeMapKey};
+
+/// A configuration file that is kept parsed in the `TypeMap` and reloaded when it changes
+pub trait WatchedFile: TypeMapKey<Value = Arc<Self>> + Sized {
+    const PATH: &'static str;
+
+    /// Parse the contents of the file. Lines that fail to parse are skipped and returned
+    /// as `(line number, reason)` so that they can be logged.
+    fn parse(contents: &str) -> (Self, Vec<(usize, String)>);
+}
+
+/// The ids of the roles that members can give themselves with `/role`
+pub struct SelfServiceRoles(pub Vec<u64>);
+
+impl TypeMapKey for SelfServiceRoles {
+    type Value = Arc<SelfServiceRoles>;
+}
+
+impl WatchedFile for SelfServiceRoles {
+    const PATH: &'static str = "self_service_roles.txt";
+
+    fn parse(contents: &str) -> (Self, Vec<(usize, String)>) {
+        let (ids, errors) = parse_id_list(contents);
+        (Self(ids), errors)
+    }
+}
+
+/// The ids of the users that can't win the activity award
+pub struct AwardBlacklist(pub Vec<u64>);
+
+impl TypeMapKey for AwardBlacklist {
+    type Value = Arc<AwardBlacklist>;
+}
+
+impl WatchedFile for AwardBlacklist {
+    const PATH: &'static str = "award_id_blacklist.txt";
+
+    fn parse(contents: &str) -> (Self, Vec<(usize, String)>) {
+        let (ids, errors) = parse_id_list(contents);
+        (Self(ids), errors)
+    }
+}
+
+/// Domains that are deleted on sight by the anti-spam module, one per line. Lines starting
+/// with `#` are comments.
+pub struct PhishingDomains(pub HashSet<String>);
+
+impl TypeMapKey for PhishingDomains {
+    type Value = Arc<PhishingDomains>;
+}
+
+impl WatchedFile for PhishingDomains {
+    const PATH: &'static str = "phishing_domains.txt";
+
+    fn parse(contents: &str) -> (Self, Vec<(usize, String)>) {
+        let mut domains = HashSet::new();
+        let mut errors = Vec::new();
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.contains(|c: char| c.is_whitespace() || c == '/') {
+                errors.push((number + 1, format!("invalid domain `{}`", line)));
+                continue;
+            }
+            domains.insert(line.to_lowercase());
+        }
+        (Self(domains), errors)
+    }
+}
+
+fn parse_id_list(contents: &str) -> (Vec<u64>, Vec<(usize, String)>) {
+    let mut ids = Vec::new();
+    let mut errors = Vec::new();
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match line.parse::<u64>() {
+            Ok(id) => ids.push(id),
+            Err(e) => errors.push((number + 1, format!("invalid id `{}`: {}", line, e))),
+        }
+    }
+    (ids, errors)
+}
+
+struct WatchState<T: WatchedFile> {
+    last_modified: Option<SystemTime>,
+    _file: PhantomData<T>,
+}
+
+impl<T: WatchedFile> WatchState<T> {
+    fn new() -> Self {
+        Self {
+            last_modified: None,
+            _file: PhantomData,
+        }
+    }
+
+    /// Re-parse the file if it has been modified since the last poll and swap the new version
+    /// into the `TypeMap`. A missing file is created empty like before.
+    async fn poll(&mut self, data: &RwLock<TypeMap>) {
+        let modified = match std::fs::metadata(T::PATH).and_then(|m| m.modified()) {
+            Ok(m) => m,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                if let Err(e) = std::fs::File::create(T::PATH) {
+                    error!("Unable to create {}: {}", T::PATH, e);
+                    return;
+                }
+                SystemTime::now()
+            }
+            Err(e) => {
+                error!("Unable to access {}: {}", T::PATH, e);
+                return;
+            }
+        };
+
+        if self.last_modified == Some(modified) {
+            return;
+        }
+
+        let contents = match std::fs::read_to_string(T::PATH) {
+            Ok(s) => s,
+            Err(e) => {
+                error!("Unable to read {}: {}", T::PATH, e);
+                return;
+            }
+        };
+
+        let (parsed, errors) = T::parse(&contents);
+        for (line, reason) in errors {
+            warn!("Skipping {}:{}: {}", T::PATH, line, reason);
+        }
+        data.write().await.insert::<T>(Arc::new(parsed));
+        self.last_modified = Some(modified);
+        info!("Loaded {}", T::PATH);
+    }
+}
+
+/// Polls the plain-text configuration files and keeps their parsed versions in the `TypeMap`,
+/// so that event handlers never have to touch the filesystem
+pub struct FileWatcher {
+    self_service_roles: WatchState<SelfServiceRoles>,
+    award_blacklist: WatchState<AwardBlacklist>,
+    phishing_domains: WatchState<PhishingDomains>,
+}
+
+impl FileWatcher {
+    pub fn new() -> Self {
+        Self {
+            self_service_roles: WatchState::new(),
+            award_blacklist: WatchState::new(),
+            phishing_domains: WatchState::new(),
+        }
+    }
+
+    pub async fn poll(&mut self, data: &RwLock<TypeMap>) {
+        self.self_service_roles.poll(data).await;
+        self.award_blacklist.poll(data).await;
+        self.phishing_domains.poll(data).await;
+    }
+
+    /// Keep polling the files forever
+    pub async fn watch(mut self, data: Arc<RwLock<TypeMap>>) {
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+            self.poll(&data).await;
+        }
+    }
+}
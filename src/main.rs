@@ -1,10 +1,16 @@
 #![feature(let_else)]
 
+mod appeals;
+mod blacklist;
 mod commands;
+mod config;
 mod database;
 mod events;
+mod evidence;
 mod extensions;
+mod file_watcher;
 mod models;
+mod moderation;
 mod schema;
 mod voting;
 
@@ -13,19 +19,21 @@ extern crate tracing;
 #[macro_use]
 extern crate diesel;
 
-use std::{collections::HashSet, env, sync::Arc};
+use std::{collections::HashSet, sync::Arc};
 
 use clokwerk::AsyncScheduler;
 use commands::owner::*;
+use config::Config;
 use database::Database;
 use extensions::*;
+use file_watcher::FileWatcher;
+use moderation::{antispam::RecentMessages, ratelimit::RateLimiter, ActionKind, ActionSource};
 use serenity::{
     async_trait,
     client::bridge::gateway::ShardManager,
     framework::{standard::macros::group, StandardFramework},
     http::Http,
     model::{
-        application::interaction::InteractionResponseType,
         channel::{Channel, ChannelType},
         event::{MessageUpdateEvent, ResumedEvent},
         gateway::{GatewayIntents, Ready},
@@ -42,26 +50,14 @@ impl TypeMapKey for ShardManagerContainer {
     type Value = Arc<Mutex<ShardManager>>;
 }
 
-pub struct BlacklistRegexes {
-    last_edited: std::time::SystemTime,
-    regexvec: Vec<regex::Regex>,
-}
-
-impl TypeMapKey for BlacklistRegexes {
-    type Value = Arc<Mutex<BlacklistRegexes>>;
-}
-
 struct Handler;
 
 #[async_trait]
 impl EventHandler for Handler {
     async fn ready(&self, ctx: Context, ready: Ready) {
         info!("Connected as {}", ready.user.name);
-        let guild_id: u64 = env::var("GUILD_ID")
-            .expect("No GUILD_ID in .env")
-            .parse()
-            .expect("Invalid GUILD_ID provided");
-        let guild_id = serenity::model::id::GuildId::from(guild_id);
+        let config = ctx.get_config().await;
+        let guild_id = serenity::model::id::GuildId::from(config.guild_id);
         guild_id
             .set_application_commands(&ctx.http, |commands| {
                 commands.create_application_command(|command| {
@@ -242,6 +238,310 @@ impl EventHandler for Handler {
                                 })
 
                 });
+                commands.create_application_command(|command| {
+                    command
+                        .name("modlog")
+                        .description("Näytä käyttäjään kohdistuneet moderointitoimenpiteet")
+                        .create_option(|option| {
+                            option
+                                .name("user")
+                                .kind(ApplicationCommandOptionType::User)
+                                .description("Käyttäjä, jonka historia näytetään")
+                                .required(true)
+                        })
+                });
+                commands.create_application_command(|command| {
+                    command
+                        .name("warn")
+                        .description("Anna käyttäjälle varoitus")
+                        .create_option(|option| {
+                            option
+                                .name("user")
+                                .kind(ApplicationCommandOptionType::User)
+                                .description("Varoitettava käyttäjä")
+                                .required(true)
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("reason")
+                                .kind(ApplicationCommandOptionType::String)
+                                .description("Varoituksen syy, lähetetään käyttäjälle")
+                                .required(true)
+                        })
+                });
+                commands.create_application_command(|command| {
+                    command
+                        .name("warnings")
+                        .description("Näytä käyttäjän varoitukset")
+                        .create_option(|option| {
+                            option
+                                .name("user")
+                                .kind(ApplicationCommandOptionType::User)
+                                .description("Käyttäjä, jonka varoitukset näytetään")
+                                .required(true)
+                        })
+                });
+                commands.create_application_command(|command| {
+                    command
+                        .name("councilstats")
+                        .description("Näytä arvojäsenten neuvoston tilastot")
+                        .create_option(|option| {
+                            option
+                                .name("days")
+                                .kind(ApplicationCommandOptionType::Integer)
+                                .description("Monenko päivän ajalta tilastot näytetään (oletus 7)")
+                                .min_int_value(1)
+                                .required(false)
+                        })
+                });
+                commands.create_application_command(|command| {
+                    command
+                        .name("mod")
+                        .description("Arvojäsenen välittömät moderointitoimenpiteet")
+                        .create_option(|option| {
+                            option
+                                .name("silence")
+                                .description("Hiljennä jäsen")
+                                .kind(ApplicationCommandOptionType::SubCommand)
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("user")
+                                        .description("Hiljennettävä jäsen")
+                                        .kind(ApplicationCommandOptionType::User)
+                                        .required(true)
+                                })
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("reason")
+                                        .description("Syy, kirjataan moderointilokiin")
+                                        .kind(ApplicationCommandOptionType::String)
+                                        .required(false)
+                                })
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("unsilence")
+                                .description("Pura jäsenen hiljennys")
+                                .kind(ApplicationCommandOptionType::SubCommand)
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("user")
+                                        .description("Jäsen, jonka hiljennys puretaan")
+                                        .kind(ApplicationCommandOptionType::User)
+                                        .required(true)
+                                })
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("reason")
+                                        .description("Syy, kirjataan moderointilokiin")
+                                        .kind(ApplicationCommandOptionType::String)
+                                        .required(false)
+                                })
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("purge")
+                                .description("Poista kanavan viimeisimmät viestit")
+                                .kind(ApplicationCommandOptionType::SubCommand)
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("count")
+                                        .description("Poistettavien viestien määrä")
+                                        .kind(ApplicationCommandOptionType::Integer)
+                                        .min_int_value(1)
+                                        .max_int_value(100)
+                                        .required(true)
+                                })
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("user")
+                                        .description("Poista vain tämän käyttäjän viestit")
+                                        .kind(ApplicationCommandOptionType::User)
+                                        .required(false)
+                                })
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("timeout")
+                                .description("Anna jäsenelle jäähy")
+                                .kind(ApplicationCommandOptionType::SubCommand)
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("user")
+                                        .description("Jäähylle laitettava jäsen")
+                                        .kind(ApplicationCommandOptionType::User)
+                                        .required(true)
+                                })
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("minutes")
+                                        .description("Jäähyn kesto minuutteina")
+                                        .kind(ApplicationCommandOptionType::Integer)
+                                        .min_int_value(1)
+                                        .max_int_value(commands::moderate::MAX_TIMEOUT_MINUTES)
+                                        .required(true)
+                                })
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("reason")
+                                        .description("Syy, kirjataan moderointilokiin")
+                                        .kind(ApplicationCommandOptionType::String)
+                                        .required(false)
+                                })
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("slowmode")
+                                .description("Aseta kanavan hidastustila")
+                                .kind(ApplicationCommandOptionType::SubCommand)
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("seconds")
+                                        .description("Viestien väli sekunteina, 0 poistaa hidastustilan")
+                                        .kind(ApplicationCommandOptionType::Integer)
+                                        .min_int_value(0)
+                                        .max_int_value(21600)
+                                        .required(true)
+                                })
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("channel")
+                                        .description("Kanava, oletuksena nykyinen")
+                                        .kind(ApplicationCommandOptionType::Channel)
+                                        .required(false)
+                                })
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("lock")
+                                .description("Lukitse kanava tai ketju tai avaa sen lukitus")
+                                .kind(ApplicationCommandOptionType::SubCommand)
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("channel")
+                                        .description("Kanava, oletuksena nykyinen")
+                                        .kind(ApplicationCommandOptionType::Channel)
+                                        .required(false)
+                                })
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("locked")
+                                        .description("Lukitaanko (oletus) vai avataanko")
+                                        .kind(ApplicationCommandOptionType::Boolean)
+                                        .required(false)
+                                })
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("reason")
+                                        .description("Syy, kirjataan moderointilokiin")
+                                        .kind(ApplicationCommandOptionType::String)
+                                        .required(false)
+                                })
+                        })
+                });
+                commands.create_application_command(|command| {
+                    command
+                        .name("reports")
+                        .description("Näytä omat ilmoituksesi ja niiden käsittely")
+                        .create_option(|option| {
+                            option
+                                .name("notifications")
+                                .kind(ApplicationCommandOptionType::Boolean)
+                                .description("Lähetä yksityisviesti, kun ilmoituksesi on käsitelty")
+                                .required(false)
+                        })
+                });
+                commands.create_application_command(|command| {
+                    command
+                        .name("blacklist")
+                        .description("Hallitse kiellettyjen viestien estolistaa")
+                        .create_option(|option| {
+                            option
+                                .name("add")
+                                .description("Lisää säännöllinen lauseke estolistalle")
+                                .kind(ApplicationCommandOptionType::SubCommand)
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("pattern")
+                                        .description("Estettävä säännöllinen lauseke")
+                                        .required(true)
+                                        .kind(ApplicationCommandOptionType::String)
+                                })
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("reason")
+                                        .description("Syy estolle")
+                                        .kind(ApplicationCommandOptionType::String)
+                                })
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("action")
+                                        .description("Mitä vastaaville viesteille tehdään, oletus = poisto")
+                                        .kind(ApplicationCommandOptionType::String)
+                                        .add_string_choice("Poista viesti", "delete")
+                                        .add_string_choice("Poista ja kerro syy lähettäjälle", "dm")
+                                        .add_string_choice("Poista ja ilmoita arvojäsenille", "notify")
+                                        .add_string_choice("Poista ja aseta jäähy", "timeout")
+                                        .add_string_choice("Ilmianna arvojäsenten neuvostolle", "report")
+                                })
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("timeout")
+                                        .description("Jäähyn pituus minuutteina, oletus = 10")
+                                        .kind(ApplicationCommandOptionType::Integer)
+                                })
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("case_sensitive")
+                                        .description("Erota isot ja pienet kirjaimet, oletus = ei")
+                                        .kind(ApplicationCommandOptionType::Boolean)
+                                })
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("whole_word")
+                                        .description("Vastaa vain kokonaisia sanoja, oletus = ei")
+                                        .kind(ApplicationCommandOptionType::Boolean)
+                                })
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("normalize")
+                                        .description("Tulkitse kiertoyritykset kuten 4 → a, oletus = ei")
+                                        .kind(ApplicationCommandOptionType::Boolean)
+                                })
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("remove")
+                                .description("Poista kohta estolistalta")
+                                .kind(ApplicationCommandOptionType::SubCommand)
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("id")
+                                        .description("Estolistan kohdan tunniste")
+                                        .required(true)
+                                        .kind(ApplicationCommandOptionType::Integer)
+                                })
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("list")
+                                .description("Luetteloi estolistan kohdat")
+                                .kind(ApplicationCommandOptionType::SubCommand)
+                        })
+                        .create_option(|option| {
+                            option
+                                .name("test")
+                                .description("Näytä mitkä estolistan kohdat vastaavat tekstiä")
+                                .kind(ApplicationCommandOptionType::SubCommand)
+                                .create_sub_option(|subopt| {
+                                    subopt
+                                        .name("text")
+                                        .description("Testattava teksti")
+                                        .required(true)
+                                        .kind(ApplicationCommandOptionType::String)
+                                })
+                        })
+                });
                 commands.create_application_command(|command| {
                     command
                         .name("⛔ Ilmianna viesti")
@@ -251,10 +551,8 @@ impl EventHandler for Handler {
             .await
             .unwrap();
 
-        if let Ok(s) = env::var("STATUS_CHANNEL_ID") {
-            let status_channel_id: ChannelId =
-                s.parse().expect("Invalid STATUS_CHANNEL_ID provided");
-            status_channel_id
+        if let Some(status_channel_id) = config.status_channel_id {
+            ChannelId(status_channel_id)
                 .send_message(&ctx.http, |m| {
                     m.content(format!(
                         "Testauskoira on herännyt ja valmiina toimintaan! `{}`",
@@ -267,21 +565,64 @@ impl EventHandler for Handler {
     }
 
     async fn guild_member_update(&self, ctx: Context, old: Option<Member>, new: Member) {
+        moderation::sticky::remember(&ctx, old.as_ref(), &new).await;
+
+        let name_changed = old
+            .as_ref()
+            .is_none_or(|o| o.nick != new.nick || o.user.name != new.user.name);
+        if name_changed {
+            blacklist::enforce_member(&ctx, &new).await;
+        }
+
         if let Some(old_member) = old {
-            let silence_role = env::var("SILENCED_ROLE_ID")
-                .expect("No SILENCED_ROLE_ID in env")
-                .parse::<u64>()
-                .unwrap();
+            let silence_role = ctx.get_config().await.silenced_role_id;
             let old_silence = old_member.roles.contains(&RoleId(silence_role));
             let new_silence = new.roles.contains(&RoleId(silence_role));
+            // A silence the bot is applying is either pending or already stored, so a role
+            // change that is neither has been made by hand
             if new_silence && !old_silence {
                 let db = ctx.get_db().await;
                 info!("Silencing user: {}", &new.user);
-                db.silence_user(new.user.id.0).await.ok();
+                if !db.is_silenced(new.user.id.0).await.unwrap_or(true)
+                    && !moderation::action::is_silence_pending(&ctx, new.user.id.0).await
+                {
+                    moderation::log_action(
+                        &ctx,
+                        new.user.id.0,
+                        None,
+                        ActionKind::Silence,
+                        ActionSource::Manual,
+                        None,
+                        None,
+                    )
+                    .await;
+                    db.silence_user(new.user.id.0, None, None, None).await.ok();
+                }
             } else if old_silence && !new_silence {
                 let db = ctx.get_db().await;
                 info!("un-silencing user: {}", &new.user);
-                db.unsilence_user(new.user.id.0).await.ok();
+                let silence = db.get_silence(new.user.id.0).await.ok().flatten();
+                let now = chrono::Local::now().naive_local();
+                // Expired silences are logged and removed by `events::silence_expiry`
+                let expired = silence
+                    .as_ref()
+                    .and_then(|s| s.expires_at)
+                    .is_some_and(|expires_at| expires_at <= now);
+                if silence.is_some() && !expired {
+                    moderation::log_action(
+                        &ctx,
+                        new.user.id.0,
+                        None,
+                        ActionKind::Unsilence,
+                        ActionSource::Manual,
+                        None,
+                        None,
+                    )
+                    .await;
+                }
+                if !expired {
+                    db.unsilence_user(new.user.id.0).await.ok();
+                }
             }
         }
     }
@@ -296,6 +637,15 @@ impl EventHandler for Handler {
                 "role" => commands::role::handle_interaction(&ctx, a.to_owned()).await,
                 "giveaway" => commands::giveaway::handle_interaction(&ctx, a.to_owned()).await,
                 "vote" => commands::vote::create_vote(&ctx, a.to_owned()).await,
+                "blacklist" => commands::blacklist::handle_interaction(&ctx, a.to_owned()).await,
+                "modlog" => commands::modlog::handle_interaction(&ctx, a.to_owned()).await,
+                "warn" => commands::warnings::warn(&ctx, a.to_owned()).await,
+                "warnings" => commands::warnings::list(&ctx, a.to_owned()).await,
+                "reports" => commands::reports::handle_interaction(&ctx, a.to_owned()).await,
+                "mod" => commands::moderate::handle_interaction(&ctx, a.to_owned()).await,
+                "councilstats" => {
+                    commands::councilstats::handle_interaction(&ctx, a.to_owned()).await
+                }
                 _ => info!("Ignoring unknown interaction: `{}`", &a.data.name),
             },
             Interaction::MessageComponent(ref b) => match b.data.custom_id.as_str() {
@@ -303,6 +653,12 @@ impl EventHandler for Handler {
                 _ => {
                     if b.data.custom_id.as_str().starts_with("vote_") {
                         commands::vote::user_vote(&ctx, b.to_owned()).await;
+                    } else if b.data.custom_id.as_str().starts_with("MODLOG_") {
+                        commands::modlog::handle_component_interaction(&ctx, b.to_owned()).await;
+                    } else if b.data.custom_id.as_str().starts_with("report_category_") {
+                        voting::handle_report_category(&ctx, b.to_owned()).await;
+                    } else if b.data.custom_id.as_str().starts_with("appeal_") {
+                        appeals::handle_component_interaction(&ctx, b.to_owned()).await;
                     } else {
                         voting::handle_vote_interaction(&ctx, interaction.clone()).await;
                         commands::giveaway::handle_component_interaction(&ctx, interaction.clone())
@@ -310,6 +666,13 @@ impl EventHandler for Handler {
                     }
                 }
             },
+            Interaction::ModalSubmit(m) => {
+                if m.data.custom_id.starts_with("report_modal_") {
+                    voting::handle_report_modal(&ctx, m).await;
+                } else {
+                    appeals::handle_modal_submit(&ctx, m).await;
+                }
+            }
             _ => {}
         };
     }
@@ -317,55 +680,24 @@ impl EventHandler for Handler {
     async fn message(&self, ctx: Context, msg: Message) {
         let db = ctx.get_db().await;
 
-        // FIXME: Store in memory
-        let mut data = ctx.data.write().await;
-        let regexes = data.get_mut::<BlacklistRegexes>().unwrap();
-
-        let last_edited = std::fs::metadata("blacklist.txt")
-            .unwrap()
-            .modified()
-            .unwrap();
+        if blacklist::enforce(&ctx, &msg).await {
+            return;
+        }
 
-        if last_edited != regexes.lock().await.last_edited {
-            let words = match std::fs::read_to_string("blacklist.txt") {
-                Ok(s) => s,
-                Err(e) => {
-                    match e.kind() {
-                        std::io::ErrorKind::NotFound => {
-                            std::fs::File::create("blacklist.txt")
-                                .expect("Unable to create blacklist.txt");
-                        }
-                        _ => panic!("Unable to access blacklist.txt"),
-                    }
-                    String::new()
-                }
-            };
+        if moderation::antispam::check(&ctx, &msg).await {
+            return;
+        }
 
-            info!("Generating new blacklist regexes");
-            let mut new_vec = Vec::new();
-            for w in words.lines() {
-                if w.is_empty() {
-                    continue;
-                }
-                if let Ok(r) = regex::RegexBuilder::new(w).case_insensitive(true).build() {
-                    new_vec.push(r);
-                }
-            }
-            *regexes.lock().await = BlacklistRegexes {
-                last_edited,
-                regexvec: new_vec,
-            };
+        if moderation::ratelimit::check(&ctx, &msg).await {
+            return;
         }
 
-        for re in &regexes.lock().await.regexvec {
-            if re.is_match(&msg.content) {
-                msg.delete(&ctx.http).await.ok();
-                return;
-            }
+        if appeals::handle_dm(&ctx, &msg).await {
+            return;
         }
 
         if let Some(gid) = msg.guild_id {
-            if gid == env::var("GUILD_ID").unwrap().parse::<u64>().unwrap() && !msg.author.bot {
+            if gid == ctx.get_config().await.guild_id && !msg.author.bot {
                 if let Ok(Channel::Guild(c)) = msg.channel(&ctx.http).await {
                     match c.kind {
                         ChannelType::PrivateThread => {}
@@ -389,18 +721,9 @@ impl EventHandler for Handler {
     ) {
         voting::handle_edit(&ctx, &event).await;
 
-        if let Some(msg) = event.content {
-            let mut data = ctx.data.write().await;
-            let regexes = data.get_mut::<BlacklistRegexes>().unwrap();
-
-            for re in &regexes.lock().await.regexvec {
-                if re.is_match(&msg) {
-                    ctx.http
-                        .delete_message(event.channel_id.0, event.id.0)
-                        .await
-                        .ok();
-                    return;
-                }
+        if ctx.get_blacklist().await.is_match_update(&event) {
+            if let Ok(msg) = ctx.http.get_message(event.channel_id.0, event.id.0).await {
+                blacklist::enforce(&ctx, &msg).await;
             }
         }
     }
@@ -415,23 +738,24 @@ impl EventHandler for Handler {
         voting::handle_delete(&ctx, message_id).await;
     }
 
-    async fn guild_member_addition(&self, ctx: Context, mut member: Member) {
+    async fn guild_member_addition(&self, ctx: Context, member: Member) {
         info!("{} joined", member.user);
-        if let Ok(is_silenced) = ctx.get_db().await.is_silenced(member.user.id.0).await {
-            if is_silenced {
-                info!("Adding silenced role to user {}", member.user);
-                let silence_role = env::var("SILENCED_ROLE_ID")
-                    .expect("Expected SILENCED_ROLE_ID in .env")
-                    .parse::<u64>()
-                    .expect("Invalid SILENCED_ROLE_ID");
-                member.add_role(&ctx.http, silence_role).await.unwrap();
-            }
-        }
-        let member_role = env::var("MEMBER_ROLE_ID")
-            .expect("member role id not found in $MEMBER_ROLE_ID")
-            .parse::<u64>()
-            .expect("Invalid member role id");
-        member.clone().add_role(&ctx.http, member_role).await.ok();
+        moderation::sticky::restore(&ctx, &member).await;
+        blacklist::enforce_member(&ctx, &member).await;
+        let config = ctx.get_config().await;
+        member
+            .clone()
+            .add_role(&ctx.http, config.member_role_id)
+            .await
+            .ok();
+    }
+
+    async fn thread_create(&self, ctx: Context, thread: GuildChannel) {
+        blacklist::enforce_thread(&ctx, &thread).await;
+    }
+
+    async fn thread_update(&self, ctx: Context, thread: GuildChannel) {
+        blacklist::enforce_thread(&ctx, &thread).await;
     }
 
     async fn resume(&self, _: Context, _: ResumedEvent) {
@@ -445,18 +769,24 @@ struct General;
 
 #[tokio::main]
 async fn main() {
-    dotenv::dotenv().expect("Failed to load .env file");
+    // The configuration may also come from the environment or a TOML file only
+    dotenv::dotenv().ok();
 
     tracing_subscriber::fmt::init();
 
-    let database = Arc::new(Database::new().await);
+    let config = match Config::load() {
+        Ok(c) => Arc::new(c),
+        Err(e) => {
+            error!("{}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let database = Arc::new(Database::new(&config.database_url).await);
     let pending_edits = PendingEdits::new();
 
-    let token = env::var("DISCORD_TOKEN").expect("Expected a token in the environment");
-    let application_id = env::var("APPLICATION_ID")
-        .expect("Expected an application id")
-        .parse::<u64>()
-        .expect("Invalid application id form");
+    let token = config.discord_token.clone();
+    let application_id = config.application_id;
     let http = Http::new(&token);
 
     let (owners, _bot_id) = match http.get_current_application_info().await {
@@ -469,40 +799,6 @@ async fn main() {
         Err(why) => panic!("Could not access application info: {:?}", why),
     };
 
-    let words = match std::fs::read_to_string("blacklist.txt") {
-        Ok(s) => s,
-        Err(e) => {
-            match e.kind() {
-                std::io::ErrorKind::NotFound => {
-                    std::fs::File::create("blacklist.txt").expect("Unable to create blacklist.txt");
-                }
-                _ => panic!("Unable to access blacklist.txt"),
-            }
-            String::new()
-        }
-    };
-
-    let mut regexvec = Vec::new();
-
-    for w in words.lines() {
-        if w.is_empty() {
-            continue;
-        }
-        if let Ok(re) = regex::Regex::new(w) {
-            regexvec.push(re);
-        } else {
-            info!("Skipping invalid regex in `blacklist.txt`: {}", w);
-        }
-    }
-
-    let blacklist = BlacklistRegexes {
-        last_edited: std::fs::metadata("blacklist.txt")
-            .unwrap()
-            .modified()
-            .unwrap(),
-        regexvec,
-    };
-
     let framework = StandardFramework::new()
         .configure(|c| c.owners(owners).prefix("!"))
         .group(&GENERAL_GROUP);
@@ -524,17 +820,35 @@ async fn main() {
         let mut data = client.data.write().await;
         data.insert::<ShardManagerContainer>(client.shard_manager.clone());
         data.insert::<Database>(database.clone());
-        data.insert::<BlacklistRegexes>(Arc::new(Mutex::new(blacklist)));
+        data.insert::<Config>(config.clone());
         data.insert::<PendingEdits>(Arc::new(Mutex::new(pending_edits)));
+        data.insert::<RecentMessages>(Arc::new(Mutex::new(RecentMessages::new())));
+        data.insert::<RateLimiter>(Arc::new(Mutex::new(RateLimiter::new())));
     }
 
+    match database
+        .import_blacklist_file("blacklist.txt", application_id)
+        .await
+    {
+        Ok(0) => {}
+        Ok(n) => info!("Imported {} patterns from blacklist.txt", n),
+        Err(e) => error!("Unable to import blacklist.txt: {}", e),
+    }
+    blacklist::reload(&client.data)
+        .await
+        .expect("Unable to load the blacklist");
+
+    let mut file_watcher = FileWatcher::new();
+    file_watcher.poll(&client.data).await;
+    tokio::spawn(file_watcher.watch(client.data.clone()));
+
     let shard_manager = client.shard_manager.clone();
 
     let http = client.cache_and_http.http.clone();
 
     let mut scheduler = AsyncScheduler::with_tz(chrono::Local);
 
-    events::setup_schedulers(&mut scheduler, http.clone(), database.clone());
+    events::setup_schedulers(&mut scheduler, http.clone(), client.data.clone());
 
     tokio::spawn(async move {
         loop {
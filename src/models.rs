@@ -26,14 +26,16 @@ pub struct CouncilVoting {
     pub suspect_message_send_time: chrono::NaiveDateTime,
     pub suspect_message_content: String,
     pub reporter_id: u64,
-    pub delete_votes: i32,
-    pub delete_votes_required: i32,
-    pub silence_votes: i32,
-    pub silence_votes_required: i32,
-    pub block_reporter_votes: i32,
-    pub block_reporter_votes_required: i32,
     pub moderators_online: i32,
     pub useless_clicks: i64,
+    pub deadline: chrono::NaiveDateTime,
+    pub resolution: String,
+    pub closed_at: Option<chrono::NaiveDateTime>,
+    pub closed_by: Option<u64>,
+    pub report_category: Option<String>,
+    pub report_reason: Option<String>,
+    pub reply_to: Option<String>,
+    pub context: Option<String>,
 }
 
 use crate::schema::CouncilVotings;
@@ -48,14 +50,60 @@ pub struct NewCouncilVoting {
     pub suspect_message_send_time: chrono::NaiveDateTime,
     pub suspect_message_content: String,
     pub reporter_id: u64,
-    pub delete_votes: i32,
-    pub delete_votes_required: i32,
-    pub silence_votes: i32,
-    pub silence_votes_required: i32,
-    pub block_reporter_votes: i32,
-    pub block_reporter_votes_required: i32,
     pub moderators_online: i32,
     pub useless_clicks: i64,
+    pub deadline: chrono::NaiveDateTime,
+    pub resolution: String,
+    pub report_category: Option<String>,
+    pub report_reason: Option<String>,
+    pub reply_to: Option<String>,
+    pub context: Option<String>,
+}
+
+/// A report of an already reported message, the first report is stored on `CouncilVoting`
+#[derive(Queryable, Clone, Debug)]
+#[allow(dead_code)]
+pub struct CouncilVotingReport {
+    pub id: i32,
+    pub vote_message_id: u64,
+    pub reporter_id: u64,
+    pub category: Option<String>,
+    pub reason: Option<String>,
+    pub reported_at: chrono::NaiveDateTime,
+}
+
+use crate::schema::CouncilVotingReports;
+
+#[derive(Insertable)]
+#[table_name = "CouncilVotingReports"]
+pub struct NewCouncilVotingReport {
+    pub vote_message_id: u64,
+    pub reporter_id: u64,
+    pub category: Option<String>,
+    pub reason: Option<String>,
+    pub reported_at: chrono::NaiveDateTime,
+}
+
+#[derive(Queryable, Clone, Debug)]
+#[allow(dead_code)]
+pub struct CouncilVotingThreshold {
+    pub id: i32,
+    pub vote_message_id: u64,
+    pub vote_type: i32,
+    pub votes_required: i32,
+    pub executed_at: Option<chrono::NaiveDateTime>,
+    /// What came of carrying out the action
+    pub result: Option<String>,
+}
+
+use crate::schema::CouncilVotingThresholds;
+
+#[derive(Insertable)]
+#[table_name = "CouncilVotingThresholds"]
+pub struct NewCouncilVotingThreshold {
+    pub vote_message_id: u64,
+    pub vote_type: i32,
+    pub votes_required: i32,
 }
 
 #[derive(Queryable, Clone, Debug)]
@@ -157,6 +205,10 @@ pub struct NewAwardWinner {
 pub struct SilencedMember {
     pub id: i32,
     pub user_id: u64,
+    pub issuer_id: Option<u64>,
+    pub reason: Option<String>,
+    pub silenced_at: Option<chrono::NaiveDateTime>,
+    pub expires_at: Option<chrono::NaiveDateTime>,
 }
 
 use crate::schema::SilencedMembers;
@@ -165,6 +217,10 @@ use crate::schema::SilencedMembers;
 #[table_name = "SilencedMembers"]
 pub struct NewSilencedMember {
     pub user_id: u64,
+    pub issuer_id: Option<u64>,
+    pub reason: Option<String>,
+    pub silenced_at: Option<chrono::NaiveDateTime>,
+    pub expires_at: Option<chrono::NaiveDateTime>,
 }
 
 #[derive(Queryable)]
@@ -226,3 +282,214 @@ pub struct NewVote {
     pub voter_id: u64,
     pub option_number: i32,
 }
+
+#[derive(Queryable, Clone, Debug)]
+#[allow(dead_code)]
+pub struct BlacklistEntry {
+    pub id: i32,
+    pub pattern: String,
+    pub added_by: u64,
+    pub added_at: chrono::NaiveDateTime,
+    pub reason: Option<String>,
+    pub enabled: bool,
+    pub action: String,
+    pub timeout_minutes: Option<i32>,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    pub normalize: bool,
+}
+
+use crate::schema::BlacklistEntries;
+
+#[derive(Insertable)]
+#[table_name = "BlacklistEntries"]
+pub struct NewBlacklistEntry {
+    pub pattern: String,
+    pub added_by: u64,
+    pub added_at: chrono::NaiveDateTime,
+    pub reason: Option<String>,
+    pub action: String,
+    pub timeout_minutes: Option<i32>,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    pub normalize: bool,
+}
+
+use crate::schema::BlacklistHits;
+
+#[derive(Insertable)]
+#[table_name = "BlacklistHits"]
+pub struct NewBlacklistHit {
+    pub entry_id: i32,
+    pub user_id: Option<u64>,
+    pub channel_id: Option<u64>,
+    pub message_id: Option<u64>,
+    pub content: String,
+    pub action: String,
+    pub hit_time: chrono::NaiveDateTime,
+    pub source: String,
+}
+
+#[derive(Queryable, Clone, Debug)]
+#[allow(dead_code)]
+pub struct ModerationAction {
+    pub id: i32,
+    /// `None` for actions on a channel
+    pub user_id: Option<u64>,
+    pub actor_id: Option<u64>,
+    pub kind: String,
+    pub source: String,
+    pub reason: Option<String>,
+    pub action_time: chrono::NaiveDateTime,
+    pub expires_at: Option<chrono::NaiveDateTime>,
+    pub channel_id: Option<u64>,
+}
+
+use crate::schema::ModerationActions;
+
+#[derive(Insertable)]
+#[table_name = "ModerationActions"]
+pub struct NewModerationAction {
+    pub user_id: Option<u64>,
+    pub actor_id: Option<u64>,
+    pub kind: String,
+    pub source: String,
+    pub reason: Option<String>,
+    pub action_time: chrono::NaiveDateTime,
+    pub expires_at: Option<chrono::NaiveDateTime>,
+    pub channel_id: Option<u64>,
+}
+
+#[derive(Queryable, Clone, Debug)]
+#[allow(dead_code)]
+pub struct Warning {
+    pub id: i32,
+    pub user_id: u64,
+    pub issuer_id: u64,
+    pub reason: String,
+    pub issued_at: chrono::NaiveDateTime,
+    pub expires_at: chrono::NaiveDateTime,
+}
+
+use crate::schema::Warnings;
+
+#[derive(Insertable)]
+#[table_name = "Warnings"]
+pub struct NewWarning {
+    pub user_id: u64,
+    pub issuer_id: u64,
+    pub reason: String,
+    pub issued_at: chrono::NaiveDateTime,
+    pub expires_at: chrono::NaiveDateTime,
+}
+
+#[derive(Queryable, Clone, Debug)]
+#[allow(dead_code)]
+pub struct Appeal {
+    pub id: i32,
+    pub user_id: u64,
+    pub vote_message_id: u64,
+    /// The council vote that led to the silence, if there was one
+    pub council_vote_message_id: Option<u64>,
+    pub appeal_text: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub votes_required: i32,
+    pub moderators_online: i32,
+    pub resolution: Option<String>,
+    pub resolved_at: Option<chrono::NaiveDateTime>,
+}
+
+use crate::schema::Appeals;
+
+#[derive(Insertable)]
+#[table_name = "Appeals"]
+pub struct NewAppeal {
+    pub user_id: u64,
+    pub vote_message_id: u64,
+    pub council_vote_message_id: Option<u64>,
+    pub appeal_text: String,
+    pub created_at: chrono::NaiveDateTime,
+    pub votes_required: i32,
+    pub moderators_online: i32,
+}
+
+#[derive(Queryable, Clone, Debug)]
+#[allow(dead_code)]
+pub struct AppealVote {
+    pub id: i32,
+    pub appeal_id: i32,
+    pub voter_user_id: u64,
+    pub vote_type: i32,
+}
+
+use crate::schema::AppealVotes;
+
+#[derive(Insertable)]
+#[table_name = "AppealVotes"]
+pub struct NewAppealVote {
+    pub appeal_id: i32,
+    pub voter_user_id: u64,
+    pub vote_type: i32,
+}
+
+#[derive(Queryable, Clone, Debug)]
+#[allow(dead_code)]
+pub struct ReportAttachment {
+    pub id: i32,
+    pub vote_message_id: u64,
+    pub filename: String,
+    pub url: String,
+    pub archive_path: Option<String>,
+    pub evidence_url: Option<String>,
+}
+
+use crate::schema::ReportAttachments;
+
+#[derive(Insertable)]
+#[table_name = "ReportAttachments"]
+pub struct NewReportAttachment {
+    pub vote_message_id: u64,
+    pub filename: String,
+    pub url: String,
+    pub archive_path: Option<String>,
+    pub evidence_url: Option<String>,
+}
+
+use crate::schema::ReportNotifications;
+
+/// A member who wants a direct message when their reports are resolved
+#[derive(Insertable)]
+#[table_name = "ReportNotifications"]
+pub struct NewReportNotification {
+    pub user_id: u64,
+    pub subscribed_at: chrono::NaiveDateTime,
+}
+
+use crate::schema::StickyRoles;
+
+/// A punitive role the member had when they were last seen, reapplied when they rejoin
+#[derive(Insertable)]
+#[table_name = "StickyRoles"]
+pub struct NewStickyRole {
+    pub user_id: u64,
+    pub role_id: u64,
+}
+
+/// The timeout and hidden nickname the member had when they were last seen
+#[derive(Queryable, Clone, Debug)]
+#[allow(dead_code)]
+pub struct StickyMember {
+    pub user_id: u64,
+    pub timeout_until: Option<chrono::NaiveDateTime>,
+    pub nickname: Option<String>,
+}
+
+use crate::schema::StickyMembers;
+
+#[derive(Insertable)]
+#[table_name = "StickyMembers"]
+pub struct NewStickyMember {
+    pub user_id: u64,
+    pub timeout_until: Option<chrono::NaiveDateTime>,
+    pub nickname: Option<String>,
+}
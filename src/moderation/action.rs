@@ -0,0 +1,476 @@
+//! Moderation actions as data, and the executor that carries them out, logs them and tells the
+//! member about them. Failures are returned as an [`ActionReport`] instead of panicking, so that
+//! a closed DM or a member who already left doesn't take the handler down with it.
+
+use std::{collections::HashSet, future::Future, time::Duration};
+
+use reqwest::StatusCode;
+use serenity::{
+    http::HttpError,
+    model::{
+        application::component::ButtonStyle,
+        id::{ChannelId, GuildId, UserId},
+        timestamp::Timestamp,
+    },
+    prelude::{Context, TypeMapKey},
+};
+
+use super::{log_action, ActionKind, ActionSource};
+use crate::extensions::*;
+
+/// How many times a request that fails with a transient error is tried
+const ATTEMPTS: u32 = 3;
+
+/// Discord error codes the moderators get a readable explanation of
+pub const UNKNOWN_MEMBER: isize = 10007;
+const UNKNOWN_MESSAGE: isize = 10008;
+const CANNOT_MESSAGE_USER: isize = 50007;
+const MISSING_PERMISSIONS: isize = 50013;
+
+/// Something done to a member, or to a channel because of a member
+#[derive(Clone, Debug)]
+pub enum Action {
+    Delete {
+        user_id: u64,
+        channel_id: u64,
+        message_id: u64,
+    },
+    /// Gives the silenced role for `SILENCE_DURATION_DAYS` and times the member out for as long
+    /// as Discord allows. The role is lifted by `events::silence_expiry`. `cause` completes the
+    /// sentence "Sinut on hiljennetty ..." in the notification.
+    Silence {
+        user_id: u64,
+        cause: String,
+    },
+    /// Takes away the silenced role and the timeout given with it, and sends `notice` to the
+    /// member
+    Unsilence {
+        user_id: u64,
+        notice: String,
+    },
+    Timeout {
+        user_id: u64,
+        until: chrono::DateTime<chrono::Utc>,
+        notice: Option<String>,
+    },
+    Kick {
+        user_id: u64,
+        audit_reason: String,
+    },
+    /// Takes away the member's right to report messages
+    BlockReporter {
+        user_id: u64,
+    },
+    /// Locks and archives a thread because of a message of the member
+    LockThread {
+        user_id: u64,
+        channel_id: u64,
+    },
+}
+
+impl Action {
+    pub fn user_id(&self) -> u64 {
+        match *self {
+            Self::Delete { user_id, .. }
+            | Self::Silence { user_id, .. }
+            | Self::Unsilence { user_id, .. }
+            | Self::Timeout { user_id, .. }
+            | Self::Kick { user_id, .. }
+            | Self::BlockReporter { user_id }
+            | Self::LockThread { user_id, .. } => user_id,
+        }
+    }
+
+    pub fn kind(&self) -> ActionKind {
+        match self {
+            Self::Delete { .. } => ActionKind::Delete,
+            Self::Silence { .. } => ActionKind::Silence,
+            Self::Unsilence { .. } => ActionKind::Unsilence,
+            Self::Timeout { .. } => ActionKind::Timeout,
+            Self::Kick { .. } => ActionKind::Kick,
+            Self::BlockReporter { .. } => ActionKind::BlockReporter,
+            Self::LockThread { .. } => ActionKind::LockThread,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Delete { .. } => "Poisto",
+            Self::Silence { .. } => "Hiljennys",
+            Self::Unsilence { .. } => "Hiljennyksen purku",
+            Self::Timeout { .. } => "Jäähy",
+            Self::Kick { .. } => "Potkaisu",
+            Self::BlockReporter { .. } => "Ilmiantajan esto",
+            Self::LockThread { .. } => "Ketjun lukitus",
+        }
+    }
+}
+
+/// What came of an action
+#[derive(Clone, Debug)]
+pub struct ActionReport {
+    pub label: &'static str,
+    /// Why the action failed, `None` if it was carried out
+    pub error: Option<String>,
+    /// Whether the member was told about the action, `None` if they weren't meant to be
+    pub dm: Option<Result<(), String>>,
+}
+
+impl ActionReport {
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// The report as an error if the action failed, for callers that only care whether it did
+    pub fn into_result(self) -> Result<Self, anyhow::Error> {
+        match &self.error {
+            None => Ok(self),
+            Some(_) => Err(anyhow::anyhow!(self.describe())),
+        }
+    }
+
+    /// The report as shown to the moderators, e.g. "Hiljennys tehty, yksityisviesti
+    /// epäonnistui: käyttäjä ei ota vastaan yksityisviestejä"
+    pub fn describe(&self) -> String {
+        let mut description = match &self.error {
+            None => format!("{} tehty", self.label),
+            Some(e) => format!("{} epäonnistui: {}", self.label, e),
+        };
+        match &self.dm {
+            Some(Ok(())) => description.push_str(", yksityisviesti lähetetty"),
+            Some(Err(e)) => description.push_str(&format!(", yksityisviesti epäonnistui: {}", e)),
+            None => {}
+        }
+        description
+    }
+}
+
+fn is_transient(e: &serenity::Error) -> bool {
+    match e {
+        serenity::Error::Http(e) => match e.as_ref() {
+            HttpError::UnsuccessfulRequest(response) => {
+                response.status_code.is_server_error()
+                    || response.status_code == StatusCode::TOO_MANY_REQUESTS
+            }
+            HttpError::Request(_) => true,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// The JSON error code of a failed Discord request
+pub fn error_code(e: &serenity::Error) -> Option<isize> {
+    match e {
+        serenity::Error::Http(e) => match e.as_ref() {
+            HttpError::UnsuccessfulRequest(response) => Some(response.error.code),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn describe_error(e: &anyhow::Error) -> String {
+    let Some(e) = e.downcast_ref::<serenity::Error>() else {
+        return e.to_string();
+    };
+    match error_code(e) {
+        Some(UNKNOWN_MEMBER) => String::from("jäsen ei ole enää palvelimella"),
+        Some(UNKNOWN_MESSAGE) => String::from("viestiä ei enää ole"),
+        Some(CANNOT_MESSAGE_USER) => String::from("käyttäjä ei ota vastaan yksityisviestejä"),
+        Some(MISSING_PERMISSIONS) => String::from("botilta puuttuu oikeus"),
+        _ => e.to_string(),
+    }
+}
+
+/// Sends the request again while it fails with a server error, a rate limit or a network error
+async fn retry<T, F, Fut>(mut request: F) -> serenity::Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = serenity::Result<T>>,
+{
+    let mut attempt = 1;
+    loop {
+        match request().await {
+            Err(e) if attempt < ATTEMPTS && is_transient(&e) => {
+                info!("Request failed on attempt {}, trying again: {}", attempt, e);
+                tokio::time::sleep(Duration::from_secs(1 << attempt)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// A member who has left can't lose their roles or timeout, which is fine when lifting them
+fn is_missing_member(e: &serenity::Error) -> bool {
+    error_code(e) == Some(UNKNOWN_MEMBER)
+}
+
+/// Members whose silence is being applied but not yet stored
+pub struct PendingSilences;
+
+impl TypeMapKey for PendingSilences {
+    type Value = HashSet<u64>;
+}
+
+/// Whether the bot is silencing the member right now, so that the silenced role they got
+/// wasn't given by hand
+pub async fn is_silence_pending(ctx: &Context, user_id: u64) -> bool {
+    ctx.data
+        .read()
+        .await
+        .get::<PendingSilences>()
+        .is_some_and(|pending| pending.contains(&user_id))
+}
+
+async fn set_silence_pending(ctx: &Context, user_id: u64, pending: bool) {
+    let mut data = ctx.data.write().await;
+    let silences = data.entry::<PendingSilences>().or_insert_with(HashSet::new);
+    if pending {
+        silences.insert(user_id);
+    } else {
+        silences.remove(&user_id);
+    }
+}
+
+/// Carries out the action. Returns when the action expires, if it does.
+async fn apply(
+    ctx: &Context,
+    action: &Action,
+    actor_id: Option<u64>,
+    reason: &Option<String>,
+) -> Result<Option<chrono::DateTime<chrono::Local>>, anyhow::Error> {
+    let config = ctx.get_config().await;
+    let guild_id = config.guild_id;
+    match action {
+        Action::Delete {
+            channel_id,
+            message_id,
+            ..
+        } => {
+            retry(|| ctx.http.delete_message(*channel_id, *message_id)).await?;
+            Ok(None)
+        }
+        Action::Silence { user_id, .. } => {
+            let duration = chrono::Duration::days(config.silence_duration_days);
+            let expires_at = chrono::Local::now() + duration;
+            let timeout_until =
+                Timestamp::from(chrono::Utc::now() + duration.min(chrono::Duration::days(28)));
+            // The silence is stored once the role and the timeout are in place. Until then the
+            // pending flag keeps `guild_member_update` from mistaking the role for one given by
+            // hand.
+            set_silence_pending(ctx, *user_id, true).await;
+            let result = async {
+                retry(|| {
+                    ctx.http
+                        .add_member_role(guild_id, *user_id, config.silenced_role_id, None)
+                })
+                .await?;
+                let stored = async {
+                    retry(|| {
+                        GuildId(guild_id).edit_member(&ctx.http, *user_id, |m| {
+                            m.disable_communication_until_datetime(timeout_until)
+                        })
+                    })
+                    .await?;
+                    ctx.get_db()
+                        .await
+                        .silence_user(
+                            *user_id,
+                            actor_id,
+                            reason.clone(),
+                            Some(expires_at.naive_local()),
+                        )
+                        .await
+                }
+                .await;
+                if stored.is_err() {
+                    // Nothing would take away a role without a stored silence
+                    retry(|| {
+                        ctx.http.remove_member_role(
+                            guild_id,
+                            *user_id,
+                            config.silenced_role_id,
+                            None,
+                        )
+                    })
+                    .await
+                    .ok();
+                }
+                stored
+            }
+            .await;
+            set_silence_pending(ctx, *user_id, false).await;
+            result?;
+            Ok(Some(expires_at))
+        }
+        Action::Unsilence { user_id, .. } => {
+            // Removed from the database first, so that `guild_member_update` doesn't log the
+            // role removal as a manual unsilence
+            let db = ctx.get_db().await;
+            db.unsilence_user(*user_id).await?;
+            // A member who has left can't have their timeout lifted, so it's not given back
+            // when they rejoin either
+            db.set_sticky_timeout(*user_id, None).await?;
+            match retry(|| {
+                ctx.http
+                    .remove_member_role(guild_id, *user_id, config.silenced_role_id, None)
+            })
+            .await
+            {
+                Err(e) if !is_missing_member(&e) => return Err(e.into()),
+                _ => {}
+            }
+            match retry(|| {
+                GuildId(guild_id).edit_member(&ctx.http, *user_id, |m| m.enable_communication())
+            })
+            .await
+            {
+                Err(e) if !is_missing_member(&e) => return Err(e.into()),
+                _ => {}
+            }
+            Ok(None)
+        }
+        Action::Timeout { user_id, until, .. } => {
+            retry(|| {
+                GuildId(guild_id).edit_member(&ctx.http, *user_id, |m| {
+                    m.disable_communication_until_datetime(Timestamp::from(*until))
+                })
+            })
+            .await?;
+            Ok(Some(until.with_timezone(&chrono::Local)))
+        }
+        Action::Kick {
+            user_id,
+            audit_reason,
+        } => {
+            retry(|| {
+                ctx.http
+                    .kick_member_with_reason(guild_id, *user_id, audit_reason)
+            })
+            .await?;
+            Ok(None)
+        }
+        Action::BlockReporter { user_id } => {
+            retry(|| {
+                ctx.http
+                    .add_member_role(guild_id, *user_id, config.no_reports_role_id, None)
+            })
+            .await?;
+            Ok(None)
+        }
+        Action::LockThread { channel_id, .. } => {
+            let channel = ChannelId(*channel_id);
+            retry(|| channel.edit_thread(&ctx.http, |t| t.locked(true).archived(true))).await?;
+            Ok(None)
+        }
+    }
+}
+
+/// Sends the member a direct message, with a button for appealing if `appealable`
+async fn notify(
+    ctx: &Context,
+    user_id: u64,
+    content: String,
+    appealable: bool,
+) -> Result<(), String> {
+    let channel = retry(|| UserId(user_id).create_dm_channel(&ctx.http))
+        .await
+        .map_err(|e| describe_error(&e.into()))?;
+    retry(|| {
+        channel.send_message(&ctx.http, |m| {
+            m.content(&content);
+            if appealable {
+                m.components(|c| {
+                    c.create_action_row(|r| {
+                        r.create_button(|b| {
+                            b.label("Valita hiljennyksestä");
+                            b.style(ButtonStyle::Primary);
+                            b.custom_id("appeal_open")
+                        })
+                    })
+                });
+            }
+            m
+        })
+    })
+    .await
+    .map(|_| ())
+    .map_err(|e| describe_error(&e.into()))
+}
+
+/// Carries out the action, logs it to `ModerationActions` and tells the member about it.
+/// `actor_id` is the moderator or bot that took the action and `reason` is logged with it.
+pub async fn execute(
+    ctx: &Context,
+    action: Action,
+    actor_id: Option<u64>,
+    source: ActionSource,
+    reason: Option<String>,
+) -> ActionReport {
+    let user_id = action.user_id();
+    let expires_at = match apply(ctx, &action, actor_id, &reason).await {
+        Ok(expires_at) => expires_at,
+        Err(e) => {
+            error!(
+                "Unable to carry out {} on {}: {}",
+                action.kind().as_str(),
+                user_id,
+                e
+            );
+            return ActionReport {
+                label: action.label(),
+                error: Some(describe_error(&e)),
+                dm: None,
+            };
+        }
+    };
+    log_action(
+        ctx,
+        user_id,
+        actor_id,
+        action.kind(),
+        source,
+        reason,
+        expires_at.map(|e| e.naive_local()),
+    )
+    .await;
+
+    let rules_channel_id = ctx.get_config().await.rules_channel_id;
+    let dm = match &action {
+        Action::Silence { cause, .. } => Some(
+            notify(
+                ctx,
+                user_id,
+                format!(
+                    "Sinut on hiljennetty {} <t:{}:f> asti.\n\nMikäli haluat keskusteluoikeutesi takaisin, voit valittaa hiljennyksestä arvojäsenille alla olevalla painikkeella tai lähettämällä minulle yksityisviestin. Tarkistathan sääntömme kanavalta <#{}>.",
+                    cause,
+                    expires_at.map_or(0, |e| e.timestamp()),
+                    rules_channel_id
+                ),
+                true,
+            )
+            .await,
+        ),
+        Action::Unsilence { notice, .. }
+        | Action::Timeout {
+            notice: Some(notice),
+            ..
+        } => Some(notify(ctx, user_id, notice.clone(), false).await),
+        _ => None,
+    };
+    if let Some(Err(e)) = &dm {
+        info!(
+            "Unable to notify {} of {}: {}",
+            user_id,
+            action.kind().as_str(),
+            e
+        );
+    }
+    ActionReport {
+        label: action.label(),
+        error: None,
+        dm,
+    }
+}
@@ -0,0 +1,304 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use serenity::{
+    model::{channel::Message, id::ChannelId},
+    prelude::{Context, Mutex, TypeMapKey},
+};
+
+use super::{
+    action::{self, Action},
+    log_action, ActionKind, ActionSource,
+};
+use crate::{extensions::*, voting};
+
+/// Why a message was treated as spam
+enum Detection {
+    ForeignInvite(String),
+    PhishingDomain(String),
+    MassMention(usize),
+    /// The `(channel id, message id)` pairs of every copy of the message
+    CrossChannelDuplicate(Vec<(u64, u64)>),
+}
+
+impl Detection {
+    fn describe(&self) -> String {
+        match self {
+            Self::ForeignInvite(code) => format!("Kutsu toiselle palvelimelle (`{}`)", code),
+            Self::PhishingDomain(domain) => format!("Tunnettu huijaussivusto (`{}`)", domain),
+            Self::MassMention(count) => format!("Joukkomaininta ({} mainintaa)", count),
+            Self::CrossChannelDuplicate(copies) => {
+                format!("Sama viesti {} kanavalle", copies.len())
+            }
+        }
+    }
+}
+
+struct RecentMessage {
+    sent: Instant,
+    channel_id: u64,
+    message_id: u64,
+    content: String,
+}
+
+/// The latest messages of each user, used to spot the same message being sent to several
+/// channels in a short time
+#[derive(Default)]
+pub struct RecentMessages {
+    messages: HashMap<u64, VecDeque<RecentMessage>>,
+}
+
+impl TypeMapKey for RecentMessages {
+    type Value = Arc<Mutex<RecentMessages>>;
+}
+
+impl RecentMessages {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the message and returns every copy of it the author has sent within the window,
+    /// including the message itself
+    fn record(&mut self, msg: &Message, window: Duration) -> Vec<(u64, u64)> {
+        self.messages
+            .retain(|_, m| m.back().is_some_and(|r| r.sent.elapsed() < window));
+
+        let content = msg.content.trim().to_lowercase();
+        let recent = self.messages.entry(msg.author.id.0).or_default();
+        while recent.front().is_some_and(|r| r.sent.elapsed() >= window) {
+            recent.pop_front();
+        }
+        recent.push_back(RecentMessage {
+            sent: Instant::now(),
+            channel_id: msg.channel_id.0,
+            message_id: msg.id.0,
+            content,
+        });
+
+        let content = &recent.back().unwrap().content;
+        recent
+            .iter()
+            .filter(|r| &r.content == content)
+            .map(|r| (r.channel_id, r.message_id))
+            .collect()
+    }
+
+    fn forget(&mut self, user_id: u64) {
+        self.messages.remove(&user_id);
+    }
+}
+
+/// The codes of the Discord invites in the text
+fn invite_codes(text: &str) -> Vec<String> {
+    // Invite codes are case sensitive, so only the prefixes are matched case insensitively.
+    // ASCII lowercasing keeps the byte offsets of the original text.
+    let lowercase = text.to_ascii_lowercase();
+    let mut codes = Vec::new();
+    for prefix in [
+        "discord.gg/",
+        "discord.com/invite/",
+        "discordapp.com/invite/",
+    ] {
+        for (start, _) in lowercase.match_indices(prefix) {
+            let code = text[start + prefix.len()..]
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || *c == '-')
+                .collect::<String>();
+            if !code.is_empty() {
+                codes.push(code);
+            }
+        }
+    }
+    codes
+}
+
+/// The hosts of the links in the text
+fn link_hosts(text: &str) -> Vec<String> {
+    let lowercase = text.to_lowercase();
+    let mut hosts = Vec::new();
+    for scheme in ["http://", "https://"] {
+        for (start, _) in lowercase.match_indices(scheme) {
+            let authority = lowercase[start + scheme.len()..]
+                .split(|c: char| c.is_whitespace() || "/?#>)".contains(c))
+                .next()
+                .unwrap_or_default();
+            // Drop any credentials and the port, `user@host` is a classic way to disguise a link
+            let host = authority
+                .rsplit('@')
+                .next()
+                .and_then(|h| h.split(':').next())
+                .unwrap_or_default();
+            if !host.is_empty() {
+                hosts.push(host.trim_end_matches('.').to_string());
+            }
+        }
+    }
+    hosts
+}
+
+/// Finds the host, or the domain it is a subdomain of, in the phishing list
+fn phishing_domain(hosts: &[String], domains: &HashSet<String>) -> Option<String> {
+    hosts.iter().find_map(|host| {
+        let mut candidate = host.as_str();
+        loop {
+            if domains.contains(candidate) {
+                return Some(candidate.to_string());
+            }
+            candidate = candidate.split_once('.')?.1;
+        }
+    })
+}
+
+/// The first invite in the message that leads to some other server. Invites that can't be
+/// resolved are expired or invalid and thus harmless.
+async fn foreign_invite(ctx: &Context, msg: &Message, guild_id: u64) -> Option<String> {
+    for code in invite_codes(&msg.content) {
+        match ctx.http.get_invite(&code, false, false, None).await {
+            Ok(invite) if invite.guild.as_ref().map(|g| g.id.0) != Some(guild_id) => {
+                return Some(code)
+            }
+            Ok(_) => {}
+            Err(e) => info!("Unable to resolve invite {}: {}", code, e),
+        }
+    }
+    None
+}
+
+async fn detect(ctx: &Context, msg: &Message) -> Option<Detection> {
+    let config = ctx.get_config().await;
+
+    let copies = if msg.content.trim().is_empty() {
+        Vec::new()
+    } else {
+        let window = Duration::from_secs(config.antispam_duplicate_seconds);
+        ctx.get_recent_messages()
+            .await
+            .lock()
+            .await
+            .record(msg, window)
+    };
+    let channels = copies.iter().map(|(c, _)| *c).collect::<HashSet<_>>();
+    if channels.len() >= config.antispam_duplicate_channels {
+        return Some(Detection::CrossChannelDuplicate(copies));
+    }
+
+    let mentions = msg
+        .mentions
+        .iter()
+        .filter(|u| u.id != msg.author.id)
+        .map(|u| u.id.0)
+        .collect::<HashSet<_>>()
+        .len()
+        + msg.mention_roles.len();
+    if mentions > config.antispam_max_mentions {
+        return Some(Detection::MassMention(mentions));
+    }
+
+    let domains = ctx.get_phishing_domains().await;
+    if let Some(domain) = phishing_domain(&link_hosts(&msg.content), &domains.0) {
+        return Some(Detection::PhishingDomain(domain));
+    }
+
+    foreign_invite(ctx, msg, config.guild_id)
+        .await
+        .map(Detection::ForeignInvite)
+}
+
+async fn notify_moderators(ctx: &Context, msg: &Message, detection: &Detection) {
+    let config = ctx.get_config().await;
+    let content = msg.content.chars().take(1000).collect::<String>();
+    if let Err(e) = ChannelId(config.mod_channel_id)
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.color(serenity::utils::Color::RED);
+                e.title("Roskapostittaja hiljennetty");
+                e.field(
+                    "Viestin lähettänyt",
+                    format!("<@{}>, {}", msg.author.id.0, msg.author.tag()),
+                    true,
+                );
+                e.field("Viestin kanava", format!("<#{}>", msg.channel_id.0), true);
+                e.field("Syy", detection.describe(), true);
+                e.description(format!("Viestin sisältö:\n```\n{}```", content))
+            })
+        })
+        .await
+    {
+        error!(
+            "Unable to post a spam detection to the moderation channel: {}",
+            e
+        );
+    }
+}
+
+/// Checks the message for spam. Spam is deleted, the author is silenced and the moderators
+/// are notified. Returns `true` if the message was spam.
+pub async fn check(ctx: &Context, msg: &Message) -> bool {
+    if msg.author.bot || msg.guild_id.map(|g| g.0) != Some(ctx.get_config().await.guild_id) {
+        return false;
+    }
+    let Some(detection) = detect(ctx, msg).await else {
+        return false;
+    };
+    // Checking this requires a request, so it's only done once something has been found
+    if voting::is_moderator(ctx, &msg.author).await {
+        return false;
+    }
+
+    info!(
+        "Message {} by {} detected as spam: {}",
+        msg.id.0,
+        msg.author.id.0,
+        detection.describe()
+    );
+    if let Detection::CrossChannelDuplicate(copies) = &detection {
+        ctx.get_recent_messages()
+            .await
+            .lock()
+            .await
+            .forget(msg.author.id.0);
+        for (channel_id, message_id) in copies {
+            ChannelId(*channel_id)
+                .delete_message(&ctx.http, *message_id)
+                .await
+                .ok();
+        }
+    } else {
+        msg.delete(&ctx.http).await.ok();
+    }
+
+    let bot_id = ctx.cache.current_user_id().0;
+    log_action(
+        ctx,
+        msg.author.id.0,
+        Some(bot_id),
+        ActionKind::Delete,
+        ActionSource::Automod,
+        Some(detection.describe()),
+        None,
+    )
+    .await;
+    let report = action::execute(
+        ctx,
+        Action::Silence {
+            user_id: msg.author.id.0,
+            cause: String::from("automaattisesti roskapostin lähettämisen vuoksi"),
+        },
+        Some(bot_id),
+        ActionSource::Automod,
+        Some(detection.describe()),
+    )
+    .await;
+    if !report.succeeded() {
+        error!(
+            "Unable to silence spammer {}: {}",
+            msg.author.id.0,
+            report.describe()
+        );
+    }
+    notify_moderators(ctx, msg, &detection).await;
+    true
+}
@@ -0,0 +1,430 @@
+//! The actions the council can vote on when a message is reported, and how many votes each of
+//! them needs
+
+use std::str::FromStr;
+
+use serenity::model::application::component::ButtonStyle;
+
+/// An action of the council vote. The discriminant is stored as `vote_type` in
+/// `VotingActions` and `CouncilVotingThresholds`, so existing values must never change.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CouncilAction {
+    Delete = 0,
+    Silence = 1,
+    BlockReporter = 2,
+    Timeout = 3,
+    LockThread = 4,
+    Kick = 5,
+}
+
+impl CouncilAction {
+    pub const ALL: [CouncilAction; 6] = [
+        Self::Delete,
+        Self::Silence,
+        Self::BlockReporter,
+        Self::Timeout,
+        Self::LockThread,
+        Self::Kick,
+    ];
+
+    pub fn id(&self) -> i32 {
+        *self as i32
+    }
+
+    pub fn from_id(id: i32) -> Option<Self> {
+        Self::ALL.into_iter().find(|a| a.id() == id)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Delete => "delete",
+            Self::Silence => "silence",
+            Self::BlockReporter => "block_reporter",
+            Self::Timeout => "timeout",
+            Self::LockThread => "lock_thread",
+            Self::Kick => "kick",
+        }
+    }
+
+    /// The ids of the first three actions predate this enum and are kept so that the buttons
+    /// of old votings keep working
+    pub fn custom_id(&self) -> &'static str {
+        match self {
+            Self::Delete => "delete_button",
+            Self::Silence => "ban_button",
+            Self::BlockReporter => "abuse_button",
+            Self::Timeout => "timeout_button",
+            Self::LockThread => "lock_thread_button",
+            Self::Kick => "kick_button",
+        }
+    }
+
+    pub fn from_custom_id(custom_id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|a| a.custom_id() == custom_id)
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Delete => "Poista viesti",
+            Self::Silence => "Hiljennä jäsen",
+            Self::BlockReporter => "Estä ilmoittaja",
+            Self::Timeout => "Anna jäähy",
+            Self::LockThread => "Lukitse ketju",
+            Self::Kick => "Potkaise jäsen",
+        }
+    }
+
+    /// Completes "... puolesta" in the vote counts of the voting message
+    pub fn vote_title(&self) -> &'static str {
+        match self {
+            Self::Delete => "Poistamisen",
+            Self::Silence => "Hiljennyksen",
+            Self::BlockReporter => "Ilmoittajan estämisen",
+            Self::Timeout => "Jäähyn",
+            Self::LockThread => "Ketjun lukitsemisen",
+            Self::Kick => "Potkaisun",
+        }
+    }
+
+    /// Actions that are hard to undo. The vote that would carry them out has to be confirmed.
+    pub fn needs_confirmation(&self) -> bool {
+        matches!(self, Self::Silence | Self::Kick)
+    }
+
+    /// What happened to a reported message as told to its reporters. Blocking the reporter is
+    /// not told as an outcome of the report.
+    pub fn outcome(&self) -> Option<&'static str> {
+        match self {
+            Self::Delete => Some("viesti poistettiin"),
+            Self::Silence => Some("viestin lähettäjä hiljennettiin"),
+            Self::BlockReporter => None,
+            Self::Timeout => Some("viestin lähettäjä sai jäähyn"),
+            Self::LockThread => Some("ketju lukittiin"),
+            Self::Kick => Some("viestin lähettäjä potkaistiin palvelimelta"),
+        }
+    }
+
+    pub fn button_style(&self) -> ButtonStyle {
+        match self {
+            Self::Delete | Self::LockThread => ButtonStyle::Secondary,
+            _ => ButtonStyle::Danger,
+        }
+    }
+}
+
+impl FromStr for CouncilAction {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|a| a.as_str() == s)
+            .ok_or_else(|| anyhow::anyhow!("unknown council action `{}`", s))
+    }
+}
+
+/// The state of a council voting. Only pending and actioned votings can be voted on, until
+/// their deadline passes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VotingResolution {
+    Pending,
+    /// At least one of the actions has been carried out
+    Actioned,
+    /// Closed by a moderator without any action
+    Dismissed,
+    /// Closed by the deadline without any action
+    Expired,
+}
+
+impl VotingResolution {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Actioned => "actioned",
+            Self::Dismissed => "dismissed",
+            Self::Expired => "expired",
+        }
+    }
+}
+
+impl FromStr for VotingResolution {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "pending" => Self::Pending,
+            "actioned" => Self::Actioned,
+            "dismissed" => Self::Dismissed,
+            "expired" => Self::Expired,
+            _ => anyhow::bail!("Unknown voting resolution `{}`", s),
+        })
+    }
+}
+
+/// How the number of required votes is derived from the moderators online
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Formula {
+    /// The square root of the moderators online
+    Sqrt,
+    /// A share of the moderators online, in percent
+    Percent(f32),
+    /// The same number regardless of the moderators online
+    Fixed(i32),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Threshold {
+    pub action: CouncilAction,
+    pub formula: Formula,
+    pub min: i32,
+    pub max: Option<i32>,
+}
+
+impl Threshold {
+    /// The votes required scaled by `weight` before the minimum and maximum are applied
+    pub fn weighted_votes_required(&self, mods_online: usize, weight: f32) -> i32 {
+        let votes = match self.formula {
+            Formula::Sqrt => ((mods_online as f32).sqrt() * weight).round() as i32,
+            Formula::Percent(p) => (mods_online as f32 * p / 100. * weight).ceil() as i32,
+            Formula::Fixed(n) => (n as f32 * weight).round() as i32,
+        };
+        let votes = votes.max(self.min);
+        match self.max {
+            Some(max) => votes.min(max),
+            None => votes,
+        }
+    }
+}
+
+/// The actions offered on council votings in the order of their buttons, parsed from entries
+/// of the form `action:formula[:min[:max]]`, e.g. `delete:sqrt:1:3,kick:50%:2`. The formula is
+/// `sqrt`, a percentage or a fixed number of votes. The minimum defaults to one vote, and every
+/// action needs at least one.
+#[derive(Clone, Debug)]
+pub struct CouncilActions(pub Vec<Threshold>);
+
+impl Default for CouncilActions {
+    fn default() -> Self {
+        Self(vec![
+            Threshold {
+                action: CouncilAction::Delete,
+                formula: Formula::Sqrt,
+                min: 1,
+                max: Some(3),
+            },
+            Threshold {
+                action: CouncilAction::Silence,
+                formula: Formula::Sqrt,
+                min: 1,
+                max: None,
+            },
+            Threshold {
+                action: CouncilAction::BlockReporter,
+                formula: Formula::Sqrt,
+                min: 1,
+                max: Some(3),
+            },
+        ])
+    }
+}
+
+impl CouncilActions {
+    /// The configured threshold of `action`, or the default one if the action isn't offered on
+    /// votings
+    pub fn threshold(&self, action: CouncilAction) -> Option<Threshold> {
+        self.0
+            .iter()
+            .chain(Self::default().0.iter())
+            .find(|t| t.action == action)
+            .copied()
+    }
+}
+
+impl FromStr for CouncilActions {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut thresholds = Vec::new();
+        for entry in s.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let parts = entry.split(':').map(str::trim).collect::<Vec<_>>();
+            if parts.len() < 2 || parts.len() > 4 {
+                anyhow::bail!("invalid council action `{}`", entry);
+            }
+            let action = parts[0].parse::<CouncilAction>()?;
+            let formula = match parts[1] {
+                "sqrt" => Formula::Sqrt,
+                p if p.ends_with('%') => Formula::Percent(p.trim_end_matches('%').parse()?),
+                n => Formula::Fixed(n.parse()?),
+            };
+            let min = match parts.get(2) {
+                Some(min) if !min.is_empty() => min.parse()?,
+                _ => 1,
+            };
+            let max = match parts.get(3) {
+                Some(max) if !max.is_empty() => Some(max.parse()?),
+                _ => None,
+            };
+            match formula {
+                Formula::Percent(p) if !(p > 0. && p <= 100.) => {
+                    anyhow::bail!(
+                        "the share of council action `{}` must be between 0 and 100%",
+                        parts[0]
+                    )
+                }
+                Formula::Fixed(n) if n < 1 => {
+                    anyhow::bail!("council action `{}` must need at least one vote", parts[0])
+                }
+                _ => {}
+            }
+            if min < 1 {
+                anyhow::bail!("council action `{}` must need at least one vote", parts[0]);
+            }
+            if max.is_some_and(|max| max < min) {
+                anyhow::bail!(
+                    "the maximum of council action `{}` is below its minimum",
+                    parts[0]
+                );
+            }
+            if thresholds.iter().any(|t: &Threshold| t.action == action) {
+                anyhow::bail!("council action `{}` is listed twice", parts[0]);
+            }
+            thresholds.push(Threshold {
+                action,
+                formula,
+                min,
+                max,
+            });
+        }
+        Ok(Self(thresholds))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn threshold(formula: Formula, min: i32, max: Option<i32>) -> Threshold {
+        Threshold {
+            action: CouncilAction::Delete,
+            formula,
+            min,
+            max,
+        }
+    }
+
+    #[test]
+    fn parses_council_actions() {
+        for action in CouncilAction::ALL {
+            assert_eq!(action.as_str().parse::<CouncilAction>().unwrap(), action);
+        }
+        assert!("ban".parse::<CouncilAction>().is_err());
+    }
+
+    #[test]
+    fn parses_voting_resolutions() {
+        for resolution in [
+            VotingResolution::Pending,
+            VotingResolution::Actioned,
+            VotingResolution::Dismissed,
+            VotingResolution::Expired,
+        ] {
+            assert_eq!(
+                resolution.as_str().parse::<VotingResolution>().unwrap(),
+                resolution
+            );
+        }
+        assert!("closed".parse::<VotingResolution>().is_err());
+    }
+
+    #[test]
+    fn parses_thresholds() {
+        let actions = "delete:sqrt:1:3, kick:50%:2, silence:2, timeout:sqrt::4"
+            .parse::<CouncilActions>()
+            .unwrap()
+            .0;
+        let parsed = actions
+            .iter()
+            .map(|t| (t.action, t.formula, t.min, t.max))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            parsed,
+            vec![
+                (CouncilAction::Delete, Formula::Sqrt, 1, Some(3)),
+                (CouncilAction::Kick, Formula::Percent(50.), 2, None),
+                (CouncilAction::Silence, Formula::Fixed(2), 1, None),
+                (CouncilAction::Timeout, Formula::Sqrt, 1, Some(4)),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_thresholds() {
+        for invalid in [
+            "delete",
+            "delete:sqrt:1:2:3",
+            "nuke:sqrt",
+            "delete:many",
+            "delete:sqrt,delete:2",
+            "delete:sqrt:3:2",
+            "delete:sqrt:0",
+            "delete:sqrt:1:0",
+            "delete:0",
+            "delete:0%",
+            "delete:150%",
+        ] {
+            assert!(
+                invalid.parse::<CouncilActions>().is_err(),
+                "`{}` was accepted",
+                invalid
+            );
+        }
+    }
+
+    #[test]
+    fn requires_the_square_root_of_mods_online() {
+        let sqrt = threshold(Formula::Sqrt, 1, None);
+        assert_eq!(sqrt.weighted_votes_required(9, 1.), 3);
+        assert_eq!(sqrt.weighted_votes_required(5, 1.), 2);
+        assert_eq!(sqrt.weighted_votes_required(0, 1.), 1);
+        assert_eq!(sqrt.weighted_votes_required(4, 2.), 4);
+        assert_eq!(
+            threshold(Formula::Sqrt, 1, Some(3)).weighted_votes_required(25, 1.),
+            3
+        );
+    }
+
+    #[test]
+    fn requires_a_share_of_mods_online() {
+        let half = threshold(Formula::Percent(50.), 1, None);
+        assert_eq!(half.weighted_votes_required(4, 1.), 2);
+        assert_eq!(half.weighted_votes_required(5, 1.), 3);
+        assert_eq!(half.weighted_votes_required(0, 1.), 1);
+        assert_eq!(
+            threshold(Formula::Percent(50.), 2, None).weighted_votes_required(2, 1.),
+            2
+        );
+    }
+
+    #[test]
+    fn requires_a_fixed_number_of_votes() {
+        let fixed = threshold(Formula::Fixed(2), 1, None);
+        assert_eq!(fixed.weighted_votes_required(0, 1.), 2);
+        assert_eq!(fixed.weighted_votes_required(100, 1.), 2);
+        assert_eq!(fixed.weighted_votes_required(100, 1.5), 3);
+        assert_eq!(
+            threshold(Formula::Fixed(5), 1, Some(4)).weighted_votes_required(10, 1.),
+            4
+        );
+    }
+
+    #[test]
+    fn falls_back_to_default_thresholds() {
+        let actions = "silence:2".parse::<CouncilActions>().unwrap();
+        let silence = actions.threshold(CouncilAction::Silence).unwrap();
+        assert_eq!(silence.formula, Formula::Fixed(2));
+        let delete = actions.threshold(CouncilAction::Delete).unwrap();
+        assert_eq!(delete.formula, Formula::Sqrt);
+        assert_eq!(delete.max, Some(3));
+        assert!(actions.threshold(CouncilAction::Kick).is_none());
+    }
+}
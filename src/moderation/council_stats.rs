@@ -0,0 +1,188 @@
+//! Statistics of council votings over a period, shown by `/councilstats` and posted weekly to
+//! the moderation channel
+
+use std::{cmp::Reverse, collections::HashMap};
+
+use serenity::{builder::CreateEmbed, model::id::MessageId};
+
+use super::council::{CouncilAction, VotingResolution};
+use crate::database::Database;
+
+/// The first second of 2015, from which Discord ids count their milliseconds
+const DISCORD_EPOCH: i64 = 1_420_070_400_000;
+
+/// How many entries the per-moderator and useless click lists show
+const LEADERBOARD_SIZE: usize = 10;
+
+pub struct CouncilStats {
+    pub votings: usize,
+    /// Reports including the ones added to already open votings
+    pub reports: usize,
+    pub actioned: usize,
+    /// Votings that were actioned, dismissed or expired
+    pub resolved: usize,
+    /// The median time from the report to the first action or to the dismissal
+    pub median_resolution: Option<chrono::Duration>,
+    /// Moderators by the votes they cast, most active first
+    pub votes: Vec<(u64, usize)>,
+    /// Voting messages by their useless clicks, most clicked first
+    pub useless_clicks: Vec<(u64, i64)>,
+}
+
+/// When the voting message, and so the voting, was created
+fn created_at(vote_message_id: u64) -> chrono::NaiveDateTime {
+    MessageId(vote_message_id)
+        .created_at()
+        .with_timezone(&chrono::Local)
+        .naive_local()
+}
+
+fn format_duration(duration: chrono::Duration) -> String {
+    if duration.num_hours() > 0 {
+        format!(
+            "{} h {} min",
+            duration.num_hours(),
+            duration.num_minutes() % 60
+        )
+    } else {
+        format!("{} min", duration.num_minutes())
+    }
+}
+
+impl CouncilStats {
+    /// Collects the statistics of the votings opened since the given time
+    pub async fn collect(
+        db: &Database,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<Self, anyhow::Error> {
+        let first_message_id = ((since.timestamp_millis() - DISCORD_EPOCH).max(0) as u64) << 22;
+        let votings = db.get_votings_since(first_message_id).await?;
+        let ids = votings
+            .iter()
+            .map(|v| v.vote_message_id)
+            .collect::<Vec<_>>();
+        let (reports, thresholds, votes) = db.get_voting_details(&ids).await?;
+
+        let mut resolution_times = Vec::new();
+        let mut actioned = 0;
+        let mut resolved = 0;
+        for voting in &votings {
+            let executed = thresholds
+                .iter()
+                .filter(|t| t.vote_message_id == voting.vote_message_id)
+                .filter(|t| t.executed_at.is_some())
+                .collect::<Vec<_>>();
+            // Blocking the reporter is not an action on the reported message, but it still
+            // resolves the voting
+            let first_action = executed
+                .iter()
+                .filter(|t| t.vote_type != CouncilAction::BlockReporter.id())
+                .filter_map(|t| t.executed_at)
+                .min();
+            let resolved_at = match voting.resolution.parse() {
+                Ok(VotingResolution::Actioned) if first_action.is_some() => {
+                    actioned += 1;
+                    first_action
+                }
+                Ok(VotingResolution::Dismissed) => voting.closed_at,
+                _ => None,
+            };
+            if voting.closed_at.is_some() || !executed.is_empty() {
+                resolved += 1;
+            }
+            if let Some(resolved_at) = resolved_at {
+                resolution_times.push(resolved_at - created_at(voting.vote_message_id));
+            }
+        }
+        resolution_times.sort();
+        let median_resolution = match resolution_times.len() {
+            0 => None,
+            n if n % 2 == 0 => Some((resolution_times[n / 2 - 1] + resolution_times[n / 2]) / 2),
+            n => Some(resolution_times[n / 2]),
+        };
+
+        let mut votes_by_moderator = HashMap::new();
+        for vote in &votes {
+            *votes_by_moderator.entry(vote.voter_user_id).or_insert(0) += 1;
+        }
+        let mut votes = votes_by_moderator.into_iter().collect::<Vec<_>>();
+        votes.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        let mut useless_clicks = votings
+            .iter()
+            .filter(|v| v.useless_clicks > 0)
+            .map(|v| (v.vote_message_id, v.useless_clicks))
+            .collect::<Vec<_>>();
+        useless_clicks.sort_by_key(|&(id, clicks)| (Reverse(clicks), id));
+
+        Ok(Self {
+            votings: votings.len(),
+            reports: votings.len() + reports.len(),
+            actioned,
+            resolved,
+            median_resolution,
+            votes,
+            useless_clicks,
+        })
+    }
+
+    /// Fills in the statistics as the fields of the embed
+    pub fn write_embed(&self, e: &mut CreateEmbed, guild_id: u64, mod_channel_id: u64) {
+        e.field("Äänestyksiä", self.votings, true);
+        e.field("Ilmoituksia", self.reports, true);
+        e.field(
+            "Toimenpiteisiin johtaneet",
+            if self.resolved == 0 {
+                String::from("-")
+            } else {
+                format!(
+                    "{} / {} ({:.0} %)",
+                    self.actioned,
+                    self.resolved,
+                    self.actioned as f32 / self.resolved as f32 * 100.
+                )
+            },
+            true,
+        );
+        e.field(
+            "Käsittelyajan mediaani",
+            self.median_resolution
+                .map(format_duration)
+                .unwrap_or_else(|| String::from("-")),
+            true,
+        );
+        e.field(
+            "Ääniä arvojäsenittäin",
+            if self.votes.is_empty() {
+                String::from("Ei ääniä")
+            } else {
+                self.votes
+                    .iter()
+                    .take(LEADERBOARD_SIZE)
+                    .map(|(moderator, votes)| format!("<@{}>: {}", moderator, votes))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            },
+            false,
+        );
+        e.field(
+            "Turhimmat ilmoitukset",
+            if self.useless_clicks.is_empty() {
+                String::from("Ei turhaksi merkittyjä ilmoituksia")
+            } else {
+                self.useless_clicks
+                    .iter()
+                    .take(LEADERBOARD_SIZE)
+                    .map(|(id, clicks)| {
+                        format!(
+                            "[Äänestys](https://discord.com/channels/{}/{}/{}): {}",
+                            guild_id, mod_channel_id, id, clicks
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            },
+            false,
+        );
+    }
+}
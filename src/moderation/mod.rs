@@ -0,0 +1,126 @@
+//! Automatic moderation that works on the structure of messages rather than on the
+//! patterns of the blacklist, and the log of moderation actions
+
+pub mod action;
+pub mod antispam;
+pub mod council;
+pub mod council_stats;
+pub mod ratelimit;
+pub mod reputation;
+pub mod sticky;
+pub mod warnings;
+
+use crate::{extensions::*, models::NewModerationAction};
+
+/// What was done to a member
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActionKind {
+    Delete,
+    Timeout,
+    Silence,
+    Unsilence,
+    BlockReporter,
+    HideName,
+    Warn,
+    Kick,
+    LockThread,
+    Slowmode,
+    LockChannel,
+    UnlockChannel,
+}
+
+impl ActionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Delete => "delete",
+            Self::Timeout => "timeout",
+            Self::Silence => "silence",
+            Self::Unsilence => "unsilence",
+            Self::BlockReporter => "block_reporter",
+            Self::HideName => "hide_name",
+            Self::Warn => "warn",
+            Self::Kick => "kick",
+            Self::LockThread => "lock_thread",
+            Self::Slowmode => "slowmode",
+            Self::LockChannel => "lock_channel",
+            Self::UnlockChannel => "unlock_channel",
+        }
+    }
+}
+
+/// What caused a moderation action
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ActionSource {
+    CouncilVote,
+    /// Done by hand, for example by giving a role through the Discord client
+    Manual,
+    /// The automatic checks of this module
+    Automod,
+    Blacklist,
+    /// The escalation ladder of warnings
+    Warnings,
+    /// A time-limited action running out
+    Expiry,
+}
+
+impl ActionSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CouncilVote => "council",
+            Self::Manual => "manual",
+            Self::Automod => "automod",
+            Self::Blacklist => "blacklist",
+            Self::Warnings => "warnings",
+            Self::Expiry => "expiry",
+        }
+    }
+}
+
+/// Writes an action to the `ModerationActions` table. Failures are only logged, as the action
+/// itself has already been taken. `actor_id` is `None` when it isn't known who took the action.
+pub async fn log_action(
+    ctx: &(impl ClientContextExt + Sync),
+    user_id: u64,
+    actor_id: Option<u64>,
+    kind: ActionKind,
+    source: ActionSource,
+    reason: Option<String>,
+    expires_at: Option<chrono::NaiveDateTime>,
+) {
+    let action = NewModerationAction {
+        user_id: Some(user_id),
+        actor_id,
+        kind: kind.as_str().to_string(),
+        source: source.as_str().to_string(),
+        reason,
+        action_time: chrono::Local::now().naive_local(),
+        expires_at,
+        channel_id: None,
+    };
+    if let Err(e) = ctx.get_db().await.log_moderation_action(&action).await {
+        error!("Unable to log moderation action: {}", e);
+    }
+}
+
+/// Writes an action on a channel rather than on a member to the `ModerationActions` table
+pub async fn log_channel_action(
+    ctx: &(impl ClientContextExt + Sync),
+    channel_id: u64,
+    actor_id: u64,
+    kind: ActionKind,
+    reason: Option<String>,
+) {
+    let action = NewModerationAction {
+        user_id: None,
+        actor_id: Some(actor_id),
+        kind: kind.as_str().to_string(),
+        source: ActionSource::Manual.as_str().to_string(),
+        reason,
+        action_time: chrono::Local::now().naive_local(),
+        expires_at: None,
+        channel_id: Some(channel_id),
+    };
+    if let Err(e) = ctx.get_db().await.log_moderation_action(&action).await {
+        error!("Unable to log moderation action: {}", e);
+    }
+}
@@ -0,0 +1,161 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use serenity::{
+    model::{channel::Message, id::ChannelId},
+    prelude::{Context, Mutex, TypeMapKey},
+};
+
+use super::{
+    action::{self, Action},
+    ActionSource,
+};
+use crate::{extensions::*, voting};
+
+/// The send times of the latest messages of each user on each channel
+#[derive(Default)]
+pub struct RateLimiter {
+    messages: HashMap<(u64, u64), VecDeque<Instant>>,
+}
+
+impl TypeMapKey for RateLimiter {
+    type Value = Arc<Mutex<RateLimiter>>;
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a message and returns the number of messages the user has sent to the channel
+    /// within the window
+    fn record(&mut self, user_id: u64, channel_id: u64, window: Duration) -> usize {
+        self.messages
+            .retain(|_, m| m.back().is_some_and(|t| t.elapsed() < window));
+
+        let sent = self.messages.entry((user_id, channel_id)).or_default();
+        while sent.front().is_some_and(|t| t.elapsed() >= window) {
+            sent.pop_front();
+        }
+        sent.push_back(Instant::now());
+        sent.len()
+    }
+
+    fn forget(&mut self, user_id: u64, channel_id: u64) {
+        self.messages.remove(&(user_id, channel_id));
+    }
+}
+
+/// Whether the author joined recently enough for the raid mode limit to apply
+fn is_new_member(msg: &Message, join_minutes: i64) -> bool {
+    msg.member
+        .as_ref()
+        .and_then(|m| m.joined_at)
+        .is_some_and(|joined| {
+            chrono::Utc::now().signed_duration_since(*joined)
+                < chrono::Duration::minutes(join_minutes)
+        })
+}
+
+async fn notify_moderators(ctx: &Context, msg: &Message, count: usize, raid_mode: bool) {
+    let config = ctx.get_config().await;
+    if let Err(e) = ChannelId(config.mod_channel_id)
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.color(serenity::utils::Color::ORANGE);
+                e.title("Viestitulva pysäytetty");
+                e.field(
+                    "Käyttäjä",
+                    format!("<@{}>, {}", msg.author.id.0, msg.author.tag()),
+                    true,
+                );
+                e.field("Kanava", format!("<#{}>", msg.channel_id.0), true);
+                e.field(
+                    "Jäähy",
+                    format!("{} min", config.ratelimit_timeout_minutes),
+                    true,
+                );
+                e.description(format!(
+                    "{} viestiä {} sekunnissa{}",
+                    count,
+                    config.ratelimit_seconds,
+                    if raid_mode {
+                        ", vastaliittyneen käyttäjän raja"
+                    } else {
+                        ""
+                    }
+                ))
+            })
+        })
+        .await
+    {
+        error!(
+            "Unable to post a flood detection to the moderation channel: {}",
+            e
+        );
+    }
+}
+
+/// Counts the message towards the author's limit on the channel and times the author out if
+/// the limit is crossed. Members who joined recently have a lower limit to slow down raids.
+/// Returns `true` if the author was timed out.
+pub async fn check(ctx: &Context, msg: &Message) -> bool {
+    let config = ctx.get_config().await;
+    if msg.author.bot || msg.guild_id.map(|g| g.0) != Some(config.guild_id) {
+        return false;
+    }
+
+    let window = Duration::from_secs(config.ratelimit_seconds);
+    let count =
+        ctx.get_rate_limiter()
+            .await
+            .lock()
+            .await
+            .record(msg.author.id.0, msg.channel_id.0, window);
+    let raid_mode = is_new_member(msg, config.raid_join_minutes);
+    let limit = if raid_mode {
+        config.raid_ratelimit_messages
+    } else {
+        config.ratelimit_messages
+    };
+    if count <= limit {
+        return false;
+    }
+    // Checking this requires a request, so it's only done once the limit has been crossed
+    if voting::is_moderator(ctx, &msg.author).await {
+        return false;
+    }
+    ctx.get_rate_limiter()
+        .await
+        .lock()
+        .await
+        .forget(msg.author.id.0, msg.channel_id.0);
+
+    info!(
+        "{} sent {} messages to {} in {} seconds, timing out",
+        msg.author.id.0, count, msg.channel_id.0, config.ratelimit_seconds
+    );
+    let report = action::execute(
+        ctx,
+        Action::Timeout {
+            user_id: msg.author.id.0,
+            until: chrono::Utc::now() + chrono::Duration::minutes(config.ratelimit_timeout_minutes),
+            notice: None,
+        },
+        Some(ctx.cache.current_user_id().0),
+        ActionSource::Automod,
+        Some(format!(
+            "{} viestiä kanavalle <#{}> {} sekunnissa",
+            count, msg.channel_id.0, config.ratelimit_seconds
+        )),
+    )
+    .await;
+    if !report.succeeded() {
+        return false;
+    }
+    notify_moderators(ctx, msg, count, raid_mode).await;
+    true
+}
@@ -0,0 +1,44 @@
+//! How reliable a reporter has been, judged by what came of their earlier reports
+
+use super::council::CouncilAction;
+
+/// A report that got the reporter blocked weighs as much as this many ignored ones
+const BLOCKED_WEIGHT: f32 = 3.;
+
+/// The outcomes of the closed or actioned votings a member has reported
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ReporterRecord {
+    /// Reports on which the council acted against the reported message or its author
+    pub actioned: i64,
+    /// Reports that were dismissed or expired without action
+    pub ignored: i64,
+    /// Reports that got the reporter blocked
+    pub blocked: i64,
+}
+
+impl ReporterRecord {
+    pub fn total(&self) -> i64 {
+        self.actioned + self.ignored + self.blocked
+    }
+
+    /// The share of reports that were acted on, between 0 and 1. The score is pulled towards
+    /// one half so that the first few reports don't decide it, and a new reporter has exactly
+    /// one half.
+    pub fn score(&self) -> f32 {
+        (self.actioned as f32 + 1.)
+            / (self.actioned as f32
+                + self.ignored as f32
+                + BLOCKED_WEIGHT * self.blocked as f32
+                + 2.)
+    }
+
+    /// How much the votes required by the action are scaled for a report by this reporter. A
+    /// reliable reporter's report needs fewer votes to act on and more to block them, and an
+    /// unreliable reporter's the other way around. A new reporter's report is not scaled.
+    pub fn weight(&self, action: CouncilAction) -> f32 {
+        match action {
+            CouncilAction::BlockReporter => 0.5 + self.score(),
+            _ => 1.5 - self.score(),
+        }
+    }
+}
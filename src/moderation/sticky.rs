@@ -0,0 +1,161 @@
+//! Punishments that stay over leaving and rejoining. The punitive roles, the timeout and the
+//! hidden nickname of a member are remembered as they change, and reapplied when the member
+//! comes back.
+
+use std::str::FromStr;
+
+use serenity::{
+    model::{
+        guild::Member,
+        id::{GuildId, RoleId},
+        timestamp::Timestamp,
+    },
+    prelude::Context,
+};
+
+use crate::{blacklist::NICKNAME_PLACEHOLDER, config::Config, extensions::*};
+
+/// The audit log reason of the reapplied punishments
+const RESTORE_REASON: &str = "Palautettu jäsenen palatessa palvelimelle";
+
+/// Roles that are kept over rejoining in addition to the silenced and no reports roles,
+/// written in the configuration as ids separated by commas
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StickyRoleIds(pub Vec<u64>);
+
+impl FromStr for StickyRoleIds {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ids = Vec::new();
+        for id in s.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            match id.parse() {
+                Ok(id) => ids.push(id),
+                Err(_) => anyhow::bail!("invalid role id `{}`", id),
+            }
+        }
+        Ok(Self(ids))
+    }
+}
+
+fn sticky_roles(config: &Config) -> Vec<u64> {
+    let mut roles = vec![config.silenced_role_id, config.no_reports_role_id];
+    roles.extend(&config.sticky_role_ids.0);
+    roles
+}
+
+fn timeout_until(member: &Member) -> Option<chrono::NaiveDateTime> {
+    member
+        .communication_disabled_until
+        .map(|t| t.with_timezone(&chrono::Local).naive_local())
+}
+
+fn hidden_nickname(member: &Member) -> Option<&str> {
+    member
+        .nick
+        .as_deref()
+        .filter(|n| *n == NICKNAME_PLACEHOLDER)
+}
+
+/// Records the changes to the punishments of the member. Only changes are recorded, so that
+/// the updates sent while a returning member has yet to get their punishments back don't
+/// erase them. Without the old state the punishments the member has are recorded, and none
+/// are forgotten.
+pub async fn remember(ctx: &Context, old: Option<&Member>, new: &Member) {
+    let db = ctx.get_db().await;
+    let config = ctx.get_config().await;
+    let user_id = new.user.id.0;
+
+    for role in sticky_roles(&config) {
+        let had = old.is_some_and(|o| o.roles.contains(&RoleId(role)));
+        let has = new.roles.contains(&RoleId(role));
+        let result = if has && !had {
+            db.add_sticky_role(user_id, role).await
+        } else if had && !has {
+            db.remove_sticky_role(user_id, role).await
+        } else {
+            continue;
+        };
+        if let Err(e) = result {
+            error!("Unable to remember role {} of {}: {}", role, user_id, e);
+        }
+    }
+
+    if old.map_or(new.communication_disabled_until.is_some(), |o| {
+        o.communication_disabled_until != new.communication_disabled_until
+    }) {
+        if let Err(e) = db.set_sticky_timeout(user_id, timeout_until(new)).await {
+            error!("Unable to remember the timeout of {}: {}", user_id, e);
+        }
+    }
+
+    if old.map_or(hidden_nickname(new).is_some(), |o| {
+        hidden_nickname(o) != hidden_nickname(new)
+    }) {
+        if let Err(e) = db.set_sticky_nickname(user_id, hidden_nickname(new)).await {
+            error!("Unable to remember the nickname of {}: {}", user_id, e);
+        }
+    }
+}
+
+/// Gives a returning member back the punishments they had when they left. The silenced role is
+/// only given back if the silence is still in effect, since it may have expired or been lifted
+/// while the member was away.
+pub async fn restore(ctx: &Context, member: &Member) {
+    let db = ctx.get_db().await;
+    let config = ctx.get_config().await;
+    let user_id = member.user.id.0;
+
+    let is_silenced = db.is_silenced(user_id).await.unwrap_or(false);
+    let mut roles = db.get_sticky_roles(user_id).await.unwrap_or_default();
+    if is_silenced && !roles.contains(&config.silenced_role_id) {
+        roles.push(config.silenced_role_id);
+    }
+    let configured = sticky_roles(&config);
+    for role in roles {
+        if !configured.contains(&role) || (role == config.silenced_role_id && !is_silenced) {
+            db.remove_sticky_role(user_id, role).await.ok();
+            continue;
+        }
+        info!("Giving role {} back to {}", role, member.user);
+        if let Err(e) = ctx
+            .http
+            .add_member_role(config.guild_id, user_id, role, Some(RESTORE_REASON))
+            .await
+        {
+            error!("Unable to give role {} back to {}: {}", role, user_id, e);
+        }
+    }
+
+    let Ok(Some(sticky)) = db.get_sticky_member(user_id).await else {
+        return;
+    };
+    // Only the part of the timeout that was left is given back
+    let remaining = sticky
+        .timeout_until
+        .map(|until| until - chrono::Local::now().naive_local())
+        .filter(|remaining| *remaining > chrono::Duration::zero());
+    if remaining.is_none() && sticky.nickname.is_none() {
+        return;
+    }
+    info!("Giving the timeout and nickname back to {}", member.user);
+    if let Err(e) = GuildId(config.guild_id)
+        .edit_member(&ctx.http, user_id, |m| {
+            if let Some(remaining) = remaining {
+                m.disable_communication_until_datetime(Timestamp::from(
+                    chrono::Utc::now() + remaining,
+                ));
+            }
+            if let Some(nickname) = &sticky.nickname {
+                m.nickname(nickname);
+            }
+            m
+        })
+        .await
+    {
+        error!(
+            "Unable to give the timeout and nickname back to {}: {}",
+            user_id, e
+        );
+    }
+}
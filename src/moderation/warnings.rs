@@ -0,0 +1,213 @@
+use std::{fmt, str::FromStr};
+
+use serenity::{
+    model::{id::ChannelId, user::User},
+    prelude::Context,
+};
+
+use super::{
+    action::{self, Action},
+    council::CouncilAction,
+    log_action, ActionKind, ActionSource,
+};
+use crate::{
+    extensions::*,
+    voting::{self, Report, Suspect},
+};
+
+/// What happens when a member reaches a number of active warnings
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Escalation {
+    Timeout { minutes: i64 },
+    SilenceVote,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EscalationStep {
+    pub warnings: usize,
+    pub escalation: Escalation,
+}
+
+/// The steps taken as active warnings pile up, written in the configuration as
+/// `<warnings>:timeout:<minutes>` and `<warnings>:vote` separated by commas
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EscalationLadder(pub Vec<EscalationStep>);
+
+impl Default for EscalationLadder {
+    fn default() -> Self {
+        Self(vec![
+            EscalationStep {
+                warnings: 3,
+                escalation: Escalation::Timeout { minutes: 60 },
+            },
+            EscalationStep {
+                warnings: 5,
+                escalation: Escalation::SilenceVote,
+            },
+        ])
+    }
+}
+
+impl FromStr for EscalationLadder {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut steps = Vec::new();
+        for step in s.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let parts = step.split(':').map(str::trim).collect::<Vec<_>>();
+            let warnings = parts[0].parse()?;
+            let escalation = match parts[1..] {
+                ["timeout", minutes] => Escalation::Timeout {
+                    minutes: minutes.parse()?,
+                },
+                ["vote"] => Escalation::SilenceVote,
+                _ => anyhow::bail!("invalid escalation step `{}`", step),
+            };
+            steps.push(EscalationStep {
+                warnings,
+                escalation,
+            });
+        }
+        Ok(Self(steps))
+    }
+}
+
+impl fmt::Display for Escalation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Timeout { minutes } => write!(f, "{} minuutin jäähy", minutes),
+            Self::SilenceVote => write!(f, "hiljennysäänestys"),
+        }
+    }
+}
+
+/// Opens a council vote on a summary of the member's warnings. The vote needs a message, so
+/// the summary is posted to the moderation channel and used as one.
+async fn open_silence_vote(ctx: &Context, user: &User, active: usize) -> Result<(), anyhow::Error> {
+    let config = ctx.get_config().await;
+    let warnings = ctx.get_db().await.get_warnings(user.id.0).await?;
+    let now = chrono::Local::now().naive_local();
+    let content = warnings
+        .iter()
+        .filter(|w| w.expires_at > now)
+        .map(|w| format!("- {}", w.reason))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let summary = ChannelId(config.mod_channel_id)
+        .send_message(&ctx.http, |m| {
+            m.content(format!(
+                "<@{}> on saanut {} voimassa olevaa varoitusta, hiljennyksestä äänestetään",
+                user.id.0, active
+            ))
+        })
+        .await?;
+    // The summary is the bot's own message, so the only thing to vote on is the silence, and
+    // the bot reports it like a blacklist hit rather than the moderator who gave the warning
+    voting::report_with_actions(
+        ctx,
+        Suspect {
+            user_id: user.id.0,
+            channel_id: summary.channel_id.0,
+            message_id: summary.id.0,
+            send_time: now,
+            content,
+            attachments: Vec::new(),
+            reply_to: None,
+            context: None,
+        },
+        Report {
+            reporter_id: ctx.cache.current_user_id().0,
+            category: None,
+            reason: Some(format!("{} voimassa olevaa varoitusta", active)),
+        },
+        Some(&[CouncilAction::Silence]),
+    )
+    .await;
+    Ok(())
+}
+
+async fn escalate(
+    ctx: &Context,
+    user: &User,
+    issuer_id: u64,
+    active: usize,
+    escalation: Escalation,
+) -> Result<(), anyhow::Error> {
+    info!(
+        "{} reached {} warnings, escalating: {}",
+        user.id.0, active, escalation
+    );
+    match escalation {
+        Escalation::Timeout { minutes } => {
+            action::execute(
+                ctx,
+                Action::Timeout {
+                    user_id: user.id.0,
+                    until: chrono::Utc::now() + chrono::Duration::minutes(minutes),
+                    notice: None,
+                },
+                Some(issuer_id),
+                ActionSource::Warnings,
+                Some(format!("{} voimassa olevaa varoitusta", active)),
+            )
+            .await
+            .into_result()?;
+        }
+        Escalation::SilenceVote => open_silence_vote(ctx, user, active).await?,
+    }
+    Ok(())
+}
+
+/// Stores a warning, tells the member about it and takes the step of the escalation ladder
+/// the warning reaches, if any. Returns the number of active warnings and the escalation.
+pub async fn warn(
+    ctx: &Context,
+    user: &User,
+    issuer_id: u64,
+    reason: &str,
+) -> Result<(usize, Option<Escalation>), anyhow::Error> {
+    let config = ctx.get_config().await;
+    let db = ctx.get_db().await;
+    let expires_at =
+        chrono::Local::now().naive_local() + chrono::Duration::days(config.warning_expiry_days);
+    db.add_warning(user.id.0, issuer_id, reason, expires_at)
+        .await?;
+    log_action(
+        ctx,
+        user.id.0,
+        Some(issuer_id),
+        ActionKind::Warn,
+        ActionSource::Manual,
+        Some(reason.to_string()),
+        Some(expires_at),
+    )
+    .await;
+    let active = db.count_active_warnings(user.id.0).await? as usize;
+
+    if user
+        .dm(&ctx.http, |m| {
+            m.content(format!(
+                "Sait varoituksen arvojäseniltä: {}\n\nSinulla on nyt {} voimassa olevaa varoitusta. Tarkistathan sääntömme kanavalta <#{}>.",
+                reason, active, config.rules_channel_id
+            ))
+        })
+        .await
+        .is_err()
+    {
+        info!("Unable to send warning notification to {}", user.id.0);
+    }
+
+    // Only the step that is reached exactly is taken, so that each step happens once
+    let escalation = config
+        .warning_ladder
+        .0
+        .iter()
+        .find(|s| s.warnings == active)
+        .map(|s| s.escalation);
+    if let Some(escalation) = escalation {
+        if let Err(e) = escalate(ctx, user, issuer_id, active, escalation).await {
+            error!("Unable to escalate the warnings of {}: {}", user.id.0, e);
+        }
+    }
+    Ok((active, escalation))
+}
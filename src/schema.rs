@@ -1,4 +1,28 @@
 #![allow(non_snake_case)]
+table! {
+    Appeals (id) {
+        id -> Integer,
+        user_id -> Unsigned<Bigint>,
+        vote_message_id -> Unsigned<Bigint>,
+        council_vote_message_id -> Nullable<Unsigned<Bigint>>,
+        appeal_text -> Text,
+        created_at -> Datetime,
+        votes_required -> Integer,
+        moderators_online -> Integer,
+        resolution -> Nullable<Varchar>,
+        resolved_at -> Nullable<Datetime>,
+    }
+}
+
+table! {
+    AppealVotes (id) {
+        id -> Integer,
+        appeal_id -> Integer,
+        voter_user_id -> Unsigned<Bigint>,
+        vote_type -> Integer,
+    }
+}
+
 table! {
     AwardWinners (id) {
         id -> Integer,
@@ -7,6 +31,58 @@ table! {
     }
 }
 
+table! {
+    BlacklistEntries (id) {
+        id -> Integer,
+        pattern -> Text,
+        added_by -> Unsigned<Bigint>,
+        added_at -> Datetime,
+        reason -> Nullable<Text>,
+        enabled -> Bool,
+        action -> Varchar,
+        timeout_minutes -> Nullable<Integer>,
+        case_sensitive -> Bool,
+        whole_word -> Bool,
+        normalize -> Bool,
+    }
+}
+
+table! {
+    BlacklistHits (id) {
+        id -> Integer,
+        entry_id -> Integer,
+        user_id -> Nullable<Unsigned<Bigint>>,
+        channel_id -> Nullable<Unsigned<Bigint>>,
+        message_id -> Nullable<Unsigned<Bigint>>,
+        content -> Text,
+        action -> Varchar,
+        hit_time -> Datetime,
+        source -> Varchar,
+    }
+}
+
+table! {
+    CouncilVotingReports (id) {
+        id -> Integer,
+        vote_message_id -> Unsigned<Bigint>,
+        reporter_id -> Unsigned<Bigint>,
+        category -> Nullable<Varchar>,
+        reason -> Nullable<Text>,
+        reported_at -> Datetime,
+    }
+}
+
+table! {
+    CouncilVotingThresholds (id) {
+        id -> Integer,
+        vote_message_id -> Unsigned<Bigint>,
+        vote_type -> Integer,
+        votes_required -> Integer,
+        executed_at -> Nullable<Datetime>,
+        result -> Nullable<Text>,
+    }
+}
+
 table! {
     CouncilVotings (id) {
         id -> Integer,
@@ -17,14 +93,16 @@ table! {
         suspect_message_send_time -> Datetime,
         suspect_message_content -> Text,
         reporter_id -> Unsigned<Bigint>,
-        delete_votes -> Integer,
-        delete_votes_required -> Integer,
-        silence_votes -> Integer,
-        silence_votes_required -> Integer,
-        block_reporter_votes -> Integer,
-        block_reporter_votes_required -> Integer,
         moderators_online -> Integer,
         useless_clicks -> Bigint,
+        deadline -> Datetime,
+        resolution -> Varchar,
+        closed_at -> Nullable<Datetime>,
+        closed_by -> Nullable<Unsigned<Bigint>>,
+        report_category -> Nullable<Varchar>,
+        report_reason -> Nullable<Text>,
+        reply_to -> Nullable<Text>,
+        context -> Nullable<Text>,
     }
 }
 
@@ -59,10 +137,61 @@ table! {
     }
 }
 
+table! {
+    ModerationActions (id) {
+        id -> Integer,
+        user_id -> Nullable<Unsigned<Bigint>>,
+        actor_id -> Nullable<Unsigned<Bigint>>,
+        kind -> Varchar,
+        source -> Varchar,
+        reason -> Nullable<Text>,
+        action_time -> Datetime,
+        expires_at -> Nullable<Datetime>,
+        channel_id -> Nullable<Unsigned<Bigint>>,
+    }
+}
+
+table! {
+    ReportAttachments (id) {
+        id -> Integer,
+        vote_message_id -> Unsigned<Bigint>,
+        filename -> Varchar,
+        url -> Text,
+        archive_path -> Nullable<Text>,
+        evidence_url -> Nullable<Text>,
+    }
+}
+
+table! {
+    ReportNotifications (user_id) {
+        user_id -> Unsigned<Bigint>,
+        subscribed_at -> Datetime,
+    }
+}
+
 table! {
     SilencedMembers (id) {
         id -> Integer,
         user_id -> Unsigned<Bigint>,
+        issuer_id -> Nullable<Unsigned<Bigint>>,
+        reason -> Nullable<Text>,
+        silenced_at -> Nullable<Datetime>,
+        expires_at -> Nullable<Datetime>,
+    }
+}
+
+table! {
+    StickyMembers (user_id) {
+        user_id -> Unsigned<Bigint>,
+        timeout_until -> Nullable<Datetime>,
+        nickname -> Nullable<Varchar>,
+    }
+}
+
+table! {
+    StickyRoles (user_id, role_id) {
+        user_id -> Unsigned<Bigint>,
+        role_id -> Unsigned<Bigint>,
     }
 }
 
@@ -115,20 +244,44 @@ table! {
     }
 }
 
+table! {
+    Warnings (id) {
+        id -> Integer,
+        user_id -> Unsigned<Bigint>,
+        issuer_id -> Unsigned<Bigint>,
+        reason -> Text,
+        issued_at -> Datetime,
+        expires_at -> Datetime,
+    }
+}
+
+joinable!(BlacklistHits -> BlacklistEntries (entry_id));
 joinable!(GiveawayWinners -> Giveaways (giveaway_id));
 joinable!(VoteEventOptions -> VoteEvents (vote_id));
 joinable!(Votes -> VoteEvents (vote_id));
 
 allow_tables_to_appear_in_same_query!(
+    Appeals,
+    AppealVotes,
     AwardWinners,
+    BlacklistEntries,
+    BlacklistHits,
+    CouncilVotingReports,
+    CouncilVotingThresholds,
     CouncilVotings,
     Giveaways,
     GiveawayWinners,
     messages_day_stat,
+    ModerationActions,
+    ReportAttachments,
+    ReportNotifications,
     SilencedMembers,
+    StickyMembers,
+    StickyRoles,
     SuspectMessageEdits,
     VoteEventOptions,
     VoteEvents,
     Votes,
     VotingActions,
+    Warnings,
 );
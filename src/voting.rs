@@ -1,30 +1,49 @@
 // FIXME: un-unwrap();
 
+use std::{borrow::Cow, str::FromStr};
+
 use serenity::{
     builder::EditMessage,
+    http::Http,
     model::{
+        channel::{Attachment, AttachmentType},
         id::UserId,
         interactions::InteractionApplicationCommandCallbackDataFlags,
         application::{
             interaction::{
                 application_command::ApplicationCommandInteraction,
-                InteractionResponseType::{ChannelMessageWithSource, DeferredUpdateMessage},
+                InteractionResponseType::{
+                    ChannelMessageWithSource, DeferredUpdateMessage, Modal, UpdateMessage,
+                },
                 message_component::MessageComponentInteraction,
+                modal::ModalSubmitInteraction,
             },
-            component::ButtonStyle,
+            component::{ActionRowComponent, ButtonStyle, InputTextStyle},
         },
-        timestamp::Timestamp,
     },
     prelude::TypeMapKey,
 };
 
 use crate::{
-    env,
+    evidence,
     extensions::*,
-    models::{CouncilVoting, SuspectMessageEdit, VotingAction},
-    Arc, Channel, Context, Interaction, Message, MessageId, MessageUpdateEvent, Mutex, User,
+    models::{
+        CouncilVoting, CouncilVotingReport, CouncilVotingThreshold, ReportAttachment,
+        SuspectMessageEdit, VotingAction,
+    },
+    moderation::{
+        action::{self as moderation_action, Action, ActionReport},
+        council::{CouncilAction, VotingResolution},
+        reputation::ReporterRecord,
+        ActionSource,
+    },
+    Arc, Channel, ChannelType, Context, Interaction, Message, MessageId, MessageUpdateEvent, Mutex,
+    User,
 };
 
+/// How many edits of a reported message are shown as embeds on the voting message
+const EDIT_EMBEDS: usize = 8;
+
 pub struct PendingEdits {
     edits: Vec<u64>,
 }
@@ -57,7 +76,7 @@ async fn is_reported(ctx: &Context, message_id: u64) -> bool {
     db.is_reported(message_id).await.unwrap_or(false)
 }
 
-fn filter_votes(id: i32, actions: Vec<VotingAction>) -> String {
+pub fn filter_votes(id: i32, actions: Vec<VotingAction>) -> String {
     let mut actions = actions
         .iter()
         .filter(|x| x.vote_type == id)
@@ -69,25 +88,55 @@ fn filter_votes(id: i32, actions: Vec<VotingAction>) -> String {
     actions
 }
 
+/// The category and reason of a report as shown on the voting message, `None` if the reporter
+/// gave neither. Cut to the length of an embed field.
+fn describe_report(category: &Option<String>, reason: &Option<String>) -> Option<String> {
+    let category = category
+        .as_deref()
+        .and_then(|c| c.parse::<ReportCategory>().ok())
+        .map(|c| c.label());
+    let description = match (category, reason) {
+        (Some(category), Some(reason)) => format!("{}: {}", category, reason),
+        (Some(category), None) => category.to_string(),
+        (None, Some(reason)) => reason.clone(),
+        (None, None) => return None,
+    };
+    Some(description.chars().take(1024).collect())
+}
+
+#[allow(clippy::too_many_arguments)]
 fn generate_moderation_message(
     message: &mut EditMessage,
     voting: CouncilVoting,
+    reports: Vec<CouncilVotingReport>,
+    attachments: Vec<ReportAttachment>,
+    thresholds: Vec<CouncilVotingThreshold>,
     edits: Vec<SuspectMessageEdit>,
     votes: Vec<VotingAction>,
+    reporter: ReporterRecord,
     suspect_tag: String,
+    guild_id: u64,
 ) {
-    let guild_id = env::var("GUILD_ID").expect("NO GUILD_ID in .env");
     let message_link = format!(
         "https://discord.com/channels/{}/{}/{}",
         guild_id, voting.suspect_message_channel_id, voting.suspect_message_id
     );
-    let delete_voters = filter_votes(0, votes.clone());
-    let silence_voters = filter_votes(1, votes.clone());
-    let block_reporter_voters = filter_votes(2, votes);
+    let actions = thresholds
+        .iter()
+        .filter_map(|t| CouncilAction::from_id(t.vote_type).map(|a| (a, t)))
+        .collect::<Vec<_>>();
+    // Discord allows ten embeds per message, which leaves room for the newest edits after the
+    // report itself and the notice of its closing
+    let hidden_edits = edits.len().saturating_sub(EDIT_EMBEDS);
     message.embed(|e| {
         e.color(serenity::utils::Color::RED);
         e.title("Viestistä on tehty ilmoitus!");
         e.field("Arvojäseniä paikalla", voting.moderators_online, true);
+        e.field(
+            "Äänestys päättyy",
+            format!("<t:{}:R>", voting.deadline.timestamp()),
+            true,
+        );
         e.field(
             "Viestin kanava",
             format!("<#{}>", voting.suspect_message_channel_id),
@@ -103,34 +152,110 @@ fn generate_moderation_message(
             format!("<@{}>", voting.reporter_id),
             true,
         );
+        e.field(
+            "Ilmoittajan luotettavuus",
+            if reporter.total() == 0 {
+                String::from("Ei aiempia ilmoituksia")
+            } else {
+                format!(
+                    "{:.0} % ({} johti toimenpiteisiin, {} hylättiin, {} johti estoon)",
+                    reporter.score() * 100.,
+                    reporter.actioned,
+                    reporter.ignored,
+                    reporter.blocked
+                )
+            },
+            true,
+        );
+        e.field("Ilmoittajia", reports.len() + 1, true);
+        if let Some(description) = describe_report(&voting.report_category, &voting.report_reason) {
+            e.field("Ilmoituksen syy", description, false);
+        }
+        if !reports.is_empty() {
+            let other_reports = reports
+                .iter()
+                .map(|r| match describe_report(&r.category, &r.reason) {
+                    Some(description) => format!("<@{}>: {}", r.reporter_id, description),
+                    None => format!("<@{}>", r.reporter_id),
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            e.field(
+                "Muut ilmoitukset",
+                other_reports.chars().take(1024).collect::<String>(),
+                false,
+            );
+        }
         e.description(format!(
             "Viestin sisältö:\n```\n{}```",
             voting.suspect_message_content
         ));
-        e.field(
-            format!(
-                "Poistamisen puolesta {}/{}",
-                voting.delete_votes, voting.delete_votes_required
-            ),
-            delete_voters,
-            true,
-        );
-        e.field(
-            format!(
-                "Hiljennyksen puolesta {}/{}",
-                voting.silence_votes, voting.silence_votes_required
-            ),
-            silence_voters,
-            true,
-        );
-        e.field(
-            format!(
-                "Ilmoittajan estämisen puolesta {}/{}",
-                voting.block_reporter_votes, voting.block_reporter_votes_required
-            ),
-            block_reporter_voters,
-            true,
-        );
+        if let Some(reply_to) = &voting.reply_to {
+            e.field(
+                "Vastaus viestiin",
+                reply_to.chars().take(1024).collect::<String>(),
+                false,
+            );
+        }
+        if let Some(context) = &voting.context {
+            e.field(
+                "Edeltävät viestit",
+                context.chars().take(1024).collect::<String>(),
+                false,
+            );
+        }
+        if !attachments.is_empty() {
+            let links = attachments
+                .iter()
+                .map(|a| {
+                    format!(
+                        "[{}]({})",
+                        a.filename,
+                        a.evidence_url.as_ref().unwrap_or(&a.url)
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            e.field(
+                "Liitteet",
+                links.chars().take(1024).collect::<String>(),
+                false,
+            );
+        }
+        for (action, threshold) in &actions {
+            let count = votes
+                .iter()
+                .filter(|v| v.vote_type == threshold.vote_type)
+                .count();
+            e.field(
+                format!(
+                    "{} puolesta {}/{}",
+                    action.vote_title(),
+                    count,
+                    threshold.votes_required
+                ),
+                match &threshold.result {
+                    Some(result) => format!(
+                        "{}\n\n{}",
+                        filter_votes(threshold.vote_type, votes.clone()),
+                        result
+                    ),
+                    None => filter_votes(threshold.vote_type, votes.clone()),
+                },
+                true,
+            );
+        }
+        if hidden_edits > 0 {
+            e.field(
+                "Muokkaushistoria",
+                format!(
+                    "{} muokkausta, joista {} vanhinta on piilotettu. Koko historian näet painikkeella \"Näytä muokkaushistoria\".",
+                    edits.len(),
+                    hidden_edits
+                ),
+                false,
+            );
+        }
         e.footer(|f| {
             f.text(format!(
                 "Viesti lähetetty: {}",
@@ -138,7 +263,7 @@ fn generate_moderation_message(
             ))
         })
     });
-    for edit in &edits {
+    for edit in edits.iter().skip(hidden_edits) {
         if edit.new_content.is_empty() {
             message.add_embed(|e| {
                 e.title("Viesti on poistettu");
@@ -152,43 +277,70 @@ fn generate_moderation_message(
             e.footer(|f| f.text(format!("Muokkausajankohta: {}", edit.edit_time)))
         });
     }
+    let resolution = voting
+        .resolution
+        .parse()
+        .unwrap_or(VotingResolution::Pending);
+    let closed_at = voting.closed_at.unwrap_or(voting.deadline);
+    match resolution {
+        VotingResolution::Dismissed => {
+            message.add_embed(|e| {
+                e.title("Ilmoitus on hylätty");
+                if let Some(closed_by) = voting.closed_by {
+                    e.description(format!("Hylkääjä: <@{}>", closed_by));
+                }
+                e.footer(|f| f.text(format!("Hylkäysajankohta: {}", closed_at)))
+            });
+        }
+        VotingResolution::Expired => {
+            message.add_embed(|e| {
+                e.title("Äänestys on vanhentunut");
+                e.footer(|f| f.text(format!("Vanhenemisajankohta: {}", closed_at)))
+            });
+        }
+        VotingResolution::Pending | VotingResolution::Actioned => {}
+    }
+    let closed = voting.closed_at.is_some();
+    let deleted = edits.last().is_some_and(|e| e.new_content.is_empty());
     message.components(|c| {
-        c.create_action_row(|r| {
-            r.create_button(|b| {
-                b.label("Poista viesti");
-                b.style(ButtonStyle::Secondary);
-                if voting.delete_votes == voting.delete_votes_required
-                    || (!edits.is_empty() && edits.last().unwrap().new_content.is_empty())
-                {
-                    b.disabled(true);
+        // Discord allows five buttons per row
+        for row in actions.chunks(5) {
+            c.create_action_row(|r| {
+                for (action, threshold) in row {
+                    r.create_button(|b| {
+                        b.label(action.label());
+                        b.style(action.button_style());
+                        if closed
+                            || threshold.executed_at.is_some()
+                            || (*action == CouncilAction::Delete && deleted)
+                        {
+                            b.disabled(true);
+                        }
+                        b.custom_id(action.custom_id())
+                    });
                 }
-                b.custom_id("delete_button")
+                r
             });
+        }
+        c.create_action_row(|r| {
             r.create_button(|b| {
-                b.label("Hiljennä jäsen");
-                b.style(ButtonStyle::Danger);
-                if voting.silence_votes == voting.silence_votes_required {
-                    b.disabled(true);
-                }
-                b.custom_id("ban_button")
+                b.label("Näytä viesti");
+                b.style(ButtonStyle::Link);
+                b.url(message_link)
             });
-            if !message_link.is_empty() {
+            if !edits.is_empty() {
                 r.create_button(|b| {
-                    b.label("Näytä viesti");
-                    b.style(ButtonStyle::Link);
-                    b.url(message_link)
+                    b.label("Näytä muokkaushistoria");
+                    b.style(ButtonStyle::Secondary);
+                    b.custom_id("edit_history_button")
                 });
             }
             r.create_button(|b| {
-                b.label("Estä ilmoittaja");
-                b.style(ButtonStyle::Danger);
-                if voting.block_reporter_votes == voting.block_reporter_votes_required {
-                    b.disabled(true);
-                }
-                b.custom_id("abuse_button")
-            })
-        });
-        c.create_action_row(|r| {
+                b.label("Hylkää ilmoitus");
+                b.style(ButtonStyle::Secondary);
+                b.disabled(closed || resolution != VotingResolution::Pending);
+                b.custom_id("dismiss_button")
+            });
             r.create_button(|b| {
                 b.label(format!("{} klikkausta tuhlattu", voting.useless_clicks));
                 b.style(ButtonStyle::Success);
@@ -199,40 +351,60 @@ fn generate_moderation_message(
 }
 
 async fn update_voting_message(ctx: &Context, voting_message_id: u64) {
-    let moderation_channel_id: u64 = env::var("MOD_CHANNEL_ID")
-        .expect("No MOD_CHANNEL_ID in .env")
-        .parse()
-        .expect("Invalid MOD_CHANNEL_ID provided");
-    let db = ctx.get_db().await;
-    let event = db.get_voting_event(voting_message_id).await.unwrap();
-    let votes = db.get_voting_event_votes(voting_message_id).await.unwrap();
-    let edits = db.get_voting_event_edits(voting_message_id).await.unwrap();
-    let mut message = ctx
-        .http
-        .get_message(moderation_channel_id, voting_message_id)
+    refresh_voting_message(&ctx.http, ctx, voting_message_id)
         .await
-        .unwrap();
-    let suspect_tag = if let Ok(user) = UserId(event.suspect_id).to_user(&ctx.http).await {
+        .unwrap()
+}
+
+/// Redraws the voting message on the moderation channel from the database
+pub async fn refresh_voting_message(
+    http: &Arc<Http>,
+    data: &(impl ClientContextExt + Sync),
+    voting_message_id: u64,
+) -> Result<(), anyhow::Error> {
+    let config = data.get_config().await;
+    let db = data.get_db().await;
+    let event = db.get_voting_event(voting_message_id).await?;
+    let reports = db.get_reports(voting_message_id).await?;
+    let attachments = db.get_report_attachments(voting_message_id).await?;
+    let thresholds = db.get_voting_thresholds(voting_message_id).await?;
+    let votes = db.get_voting_event_votes(voting_message_id).await?;
+    let edits = db.get_voting_event_edits(voting_message_id).await?;
+    let reporter = db.get_reporter_record(event.reporter_id).await?;
+    let mut message = http
+        .get_message(config.mod_channel_id, voting_message_id)
+        .await?;
+    let suspect_tag = if let Ok(user) = UserId(event.suspect_id).to_user(http).await {
         user.tag()
     } else {
         String::from("[Poistettu käyttäjä]")
     };
     message
-        .edit(&ctx.http, |m| {
-            generate_moderation_message(m, event, edits, votes, suspect_tag);
+        .edit(http, |m| {
+            generate_moderation_message(
+                m,
+                event,
+                reports,
+                attachments,
+                thresholds,
+                edits,
+                votes,
+                reporter,
+                suspect_tag,
+                config.guild_id,
+            );
             m
         })
-        .await
-        .unwrap()
+        .await?;
+    Ok(())
 }
 
 /// This handles a message_changed event an checks for
 /// reported messages that are edited. It then updates the message on the
 /// moderation channel with the message's new content and the time of the edit.
 ///
-/// (Due to discord limitations the maximum number of logged edits is 9 after which they will no
-/// longer be logged)
-// NOTE: This could become a problem in which case a workaround can be implemented
+/// (Due to discord limitations only the newest edits are shown as embeds, the rest can be read
+/// from the history sent by the "Näytä muokkaushistoria" button)
 pub async fn handle_edit(ctx: &Context, event: &MessageUpdateEvent) {
     if !is_reported(ctx, event.id.0).await {
         return;
@@ -268,24 +440,11 @@ pub async fn handle_delete(ctx: &Context, message_id: MessageId) {
 /// This sends an embed to the moderation channel, containing some information about the message
 /// and the reported
 pub async fn handle_report(ctx: &Context, interaction: ApplicationCommandInteraction) {
-    let no_reports_role_id: u64 = env::var("NO_REPORTS_ROLE_ID")
-        .expect("Expected NO_REPORTS_ROLE_ID in .env")
-        .parse()
-        .expect("Invalid NO_REPORTS_ROLE_ID provided");
-
-    let guild_id: u64 = env::var("GUILD_ID")
-        .expect("Expected GUILD_ID in .env")
-        .parse()
-        .expect("Invalid GUILD_ID provided");
-
-    let moderation_channel_id = env::var("MOD_CHANNEL_ID")
-        .expect("MOD_CHANNEL_ID id expected")
-        .parse::<u64>()
-        .expect("Invalid MOD_CHANNEL_ID provided");
+    let config = ctx.get_config().await;
 
     if interaction
         .user
-        .has_role(&ctx.http, guild_id, no_reports_role_id)
+        .has_role(&ctx.http, config.guild_id, config.no_reports_role_id)
         .await
         .unwrap()
     {
@@ -305,35 +464,324 @@ pub async fn handle_report(ctx: &Context, interaction: ApplicationCommandInterac
         return;
     }
 
-    let message = if is_moderator(ctx, &interaction.user).await {
-        format!(
-            "Viesti on ilmiannettu arvojäsenten neuvostolle, <#{}>",
-            moderation_channel_id
-        )
-    } else {
-        "Viesti on ilmiannettu arvojäsenten neuvostolle".to_string()
-    };
+    if let Some(next_report) = next_allowed_report(ctx, interaction.user.id.0).await {
+        info!(
+            "Rate limiting unreliable reporter {}",
+            interaction.user.id.0
+        );
+        interaction
+            .create_interaction_response(&ctx.http, |r| {
+                r.interaction_response_data(|d| {
+                    d.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL);
+                    d.content(format!(
+                        "Suurin osa ilmoituksistasi ei ole johtanut toimenpiteisiin, joten voit tehdä seuraavan ilmoituksen <t:{}:R>.",
+                        next_report.timestamp()
+                    ))
+                });
+                r.kind(ChannelMessageWithSource)
+            })
+            .await
+            .unwrap();
+        return;
+    }
 
+    let suspect_message = interaction.data.resolved.messages.values().next().unwrap();
     interaction
         .create_interaction_response(&ctx.http, |r| {
             r.interaction_response_data(|d| {
                 d.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL);
-                d.content(message)
+                d.content("Miksi viesti pitäisi ilmiantaa?");
+                d.components(|c| {
+                    c.create_action_row(|r| {
+                        r.create_select_menu(|m| {
+                            m.custom_id(format!(
+                                "report_category_{}_{}",
+                                suspect_message.channel_id.0, suspect_message.id.0
+                            ));
+                            m.placeholder("Valitse ilmoituksen syy");
+                            m.options(|o| {
+                                for category in ReportCategory::ALL {
+                                    o.create_option(|o| {
+                                        o.label(category.label()).value(category.as_str())
+                                    });
+                                }
+                                o
+                            })
+                        })
+                    })
+                })
             });
             r.kind(ChannelMessageWithSource)
         })
         .await
         .unwrap();
-    let suspect_message = interaction.data.resolved.messages.values().next().unwrap();
-    if is_reported(ctx, suspect_message.id.0).await {
+}
+
+/// When a reporter whose reputation score is below `REPORTER_MIN_SCORE` may report again, if
+/// they have to wait
+async fn next_allowed_report(ctx: &Context, reporter_id: u64) -> Option<chrono::NaiveDateTime> {
+    let config = ctx.get_config().await;
+    let db = ctx.get_db().await;
+    if db.get_reporter_record(reporter_id).await.ok()?.score() >= config.reporter_min_score {
+        return None;
+    }
+    let next_report = db.get_last_report_time(reporter_id).await.ok()??
+        + chrono::Duration::hours(config.reporter_ratelimit_hours);
+    (next_report > chrono::Local::now().naive_local()).then_some(next_report)
+}
+
+/// Handles the category chosen for a report by asking the reporter for the reason
+pub async fn handle_report_category(ctx: &Context, component: MessageComponentInteraction) {
+    let ids = component
+        .data
+        .custom_id
+        .trim_start_matches("report_category_")
+        .to_string();
+    let Some(category) = component
+        .data
+        .values
+        .first()
+        .and_then(|c| c.parse::<ReportCategory>().ok())
+    else {
+        return;
+    };
+    component
+        .create_interaction_response(&ctx.http, |r| {
+            r.kind(Modal).interaction_response_data(|d| {
+                d.custom_id(format!("report_modal_{}_{}", ids, category.as_str()))
+                    .title("Viestin ilmiantaminen")
+                    .components(|c| {
+                        c.create_action_row(|r| {
+                            r.create_input_text(|t| {
+                                t.custom_id("report_reason")
+                                    .style(InputTextStyle::Paragraph)
+                                    .label("Kerro arvojäsenille lisää (valinnainen)")
+                                    .max_length(1000)
+                                    .required(false)
+                            })
+                        })
+                    })
+            })
+        })
+        .await
+        .unwrap();
+}
+
+/// Handles the submitted reason of a report and sends the message to the council
+pub async fn handle_report_modal(ctx: &Context, modal: ModalSubmitInteraction) {
+    let parts = modal
+        .data
+        .custom_id
+        .trim_start_matches("report_modal_")
+        .splitn(3, '_')
+        .collect::<Vec<_>>();
+    let (Some(channel_id), Some(message_id), Some(category)) = (
+        parts.first().and_then(|p| p.parse::<u64>().ok()),
+        parts.get(1).and_then(|p| p.parse::<u64>().ok()),
+        parts.get(2).and_then(|p| p.parse::<ReportCategory>().ok()),
+    ) else {
+        debug!("Malformed report modal: {}", modal.data.custom_id);
+        return;
+    };
+    let reason = modal
+        .data
+        .components
+        .iter()
+        .flat_map(|row| row.components.iter())
+        .find_map(|c| match c {
+            ActionRowComponent::InputText(t) if t.custom_id == "report_reason" => {
+                Some(t.value.trim().to_string())
+            }
+            _ => None,
+        })
+        .filter(|r| !r.is_empty());
+    let suspect_message = ctx.http.get_message(channel_id, message_id).await;
+
+    let response = match suspect_message {
+        Err(_) => "Viestiä ei löytynyt, se on ehkä jo poistettu.".to_string(),
+        Ok(_) if is_moderator(ctx, &modal.user).await => format!(
+            "Viesti on ilmiannettu arvojäsenten neuvostolle, <#{}>",
+            ctx.get_config().await.mod_channel_id
+        ),
+        Ok(_) => "Viesti on ilmiannettu arvojäsenten neuvostolle".to_string(),
+    };
+    // Replaces the category menu with the confirmation
+    modal
+        .create_interaction_response(&ctx.http, |r| {
+            r.kind(UpdateMessage)
+                .interaction_response_data(|d| d.content(response).components(|c| c))
+        })
+        .await
+        .unwrap();
+    if let Ok(suspect_message) = suspect_message {
+        report_message(
+            ctx,
+            &suspect_message,
+            Report {
+                reporter_id: modal.user.id.0,
+                category: Some(category),
+                reason,
+            },
+        )
+        .await;
+    }
+}
+
+/// Sends a reported message to the council for voting, or adds the report to the existing
+/// voting if the message has been reported already
+pub async fn report_message(ctx: &Context, suspect_message: &Message, details: Report) {
+    report(ctx, Suspect::from(suspect_message), details).await;
+}
+
+/// Why a message was reported
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportCategory {
+    Spam,
+    Harassment,
+    Nsfw,
+    Other,
+}
+
+impl ReportCategory {
+    pub const ALL: [ReportCategory; 4] = [Self::Spam, Self::Harassment, Self::Nsfw, Self::Other];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Spam => "spam",
+            Self::Harassment => "harassment",
+            Self::Nsfw => "nsfw",
+            Self::Other => "other",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Spam => "Roskaposti",
+            Self::Harassment => "Häirintä",
+            Self::Nsfw => "NSFW",
+            Self::Other => "Muu",
+        }
+    }
+}
+
+impl FromStr for ReportCategory {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "spam" => Self::Spam,
+            "harassment" => Self::Harassment,
+            "nsfw" => Self::Nsfw,
+            "other" => Self::Other,
+            _ => anyhow::bail!("Unknown report category `{}`", s),
+        })
+    }
+}
+
+/// Who reported a message and why. Reports made by the bot itself have no category.
+pub struct Report {
+    pub reporter_id: u64,
+    pub category: Option<ReportCategory>,
+    pub reason: Option<String>,
+}
+
+/// The message a council vote is about
+pub struct Suspect {
+    pub user_id: u64,
+    pub channel_id: u64,
+    pub message_id: u64,
+    pub send_time: chrono::NaiveDateTime,
+    pub content: String,
+    pub attachments: Vec<Attachment>,
+    /// The message the suspect message replied to
+    pub reply_to: Option<String>,
+    /// The messages sent before the suspect message, filled in when it is reported
+    pub context: Option<String>,
+}
+
+impl From<&Message> for Suspect {
+    fn from(message: &Message) -> Self {
+        Self {
+            user_id: message.author.id.0,
+            channel_id: message.channel_id.0,
+            message_id: message.id.0,
+            send_time: message.timestamp.naive_local(),
+            content: evidence::message_text(message),
+            attachments: message.attachments.clone(),
+            reply_to: evidence::reply_text(message),
+            context: None,
+        }
+    }
+}
+
+/// Sends a suspect to the council for voting, or adds the report to the existing voting if the
+/// message has been reported already
+pub async fn report(ctx: &Context, suspect: Suspect, details: Report) {
+    report_with_actions(ctx, suspect, details, None).await
+}
+
+/// Like `report`, but only offers the given actions of the configured ones on a new voting
+pub async fn report_with_actions(
+    ctx: &Context,
+    mut suspect: Suspect,
+    details: Report,
+    actions: Option<&[CouncilAction]>,
+) {
+    let db = ctx.get_db().await;
+    if let Ok(voting) = db.get_voting_event_for_message(suspect.message_id).await {
+        if voting.reporter_id == details.reporter_id
+            || db
+                .add_report(voting.vote_message_id, details)
+                .await
+                .unwrap()
+                == 0
+        {
+            info!(
+                "The message {} is already reported by the same user! Skipping...",
+                suspect.message_id
+            );
+            return;
+        }
         info!(
-            "The message {} is already reported! Skipping...",
-            suspect_message.id.0
+            "The message {} is already reported, adding the report to the voting",
+            suspect.message_id
         );
+        update_voting_message(ctx, voting.vote_message_id).await;
         return;
     }
+    let config = ctx.get_config().await;
+    // Votings opened by the bot about its own messages on the moderation channel need no context
+    if suspect.channel_id != config.mod_channel_id {
+        suspect.context =
+            evidence::fetch_context(ctx, suspect.channel_id, suspect.message_id).await;
+    }
+    let attachments = std::mem::take(&mut suspect.attachments);
     let mods_online = get_online_mod_count(ctx).await;
-    let moderation_channel = ctx.http.get_channel(moderation_channel_id).await.unwrap();
+    let in_thread = matches!(
+        ctx.http.get_channel(suspect.channel_id).await,
+        Ok(Channel::Guild(c)) if matches!(
+            c.kind,
+            ChannelType::PublicThread | ChannelType::PrivateThread | ChannelType::NewsThread
+        )
+    );
+    let reporter = db
+        .get_reporter_record(details.reporter_id)
+        .await
+        .unwrap_or_default();
+    let thresholds = config
+        .council_actions
+        .0
+        .iter()
+        .filter(|t| t.action != CouncilAction::LockThread || in_thread)
+        .filter(|t| actions.is_none_or(|a| a.contains(&t.action)))
+        .map(|t| {
+            (
+                t.action,
+                t.weighted_votes_required(mods_online, reporter.weight(t.action)),
+            )
+        })
+        .collect();
+    let moderation_channel = ctx.http.get_channel(config.mod_channel_id).await.unwrap();
     let voting_message = moderation_channel
         .id()
         .send_message(&ctx.http, |m| {
@@ -341,26 +789,25 @@ pub async fn handle_report(ctx: &Context, interaction: ApplicationCommandInterac
         })
         .await
         .unwrap();
-    let db = ctx.get_db().await;
     db.new_reported_message(
         voting_message.id.0,
-        suspect_message.to_owned(),
-        interaction.user.id.0,
+        suspect,
+        details,
         mods_online as i32,
+        thresholds,
+        (chrono::Local::now() + chrono::Duration::hours(config.council_vote_hours)).naive_local(),
     )
     .await
     .unwrap();
+    evidence::archive_attachments(ctx, &voting_message, attachments).await;
     update_voting_message(ctx, voting_message.id.0).await;
 }
 
 /// Get the amount of online members who have access to the moderation channel.
 /// This is done by comparing the members of the channel to the member that are currently present on
 /// the server.
-async fn get_online_mod_count(ctx: &Context) -> usize {
-    let channelid = env::var("MOD_CHANNEL_ID")
-        .expect("MOD_CHANNEL_ID id expected")
-        .parse::<u64>()
-        .expect("Invalid mod role id");
+pub async fn get_online_mod_count(ctx: &Context) -> usize {
+    let channelid = ctx.get_config().await.mod_channel_id;
     if let Channel::Guild(channel) = ctx.http.get_channel(channelid).await.unwrap() {
         let precenses = ctx.cache.guild(channel.guild_id).unwrap().presences;
         let mut members = channel.members(&ctx.cache).await.unwrap();
@@ -371,11 +818,8 @@ async fn get_online_mod_count(ctx: &Context) -> usize {
 }
 
 /// Check if the given user is a moderator or not, based on their access to the moderation channel
-async fn is_moderator(ctx: &Context, user: &User) -> bool {
-    let channelid = env::var("MOD_CHANNEL_ID")
-        .expect("MOD_CHANNEL_ID id expected")
-        .parse::<u64>()
-        .expect("Invalid mod role id");
+pub async fn is_moderator(ctx: &Context, user: &User) -> bool {
+    let channelid = ctx.get_config().await.mod_channel_id;
     if let Channel::Guild(channel) = ctx.http.get_channel(channelid).await.unwrap() {
         return channel
             .permissions_for_user(&ctx.cache, user)
@@ -385,141 +829,355 @@ async fn is_moderator(ctx: &Context, user: &User) -> bool {
     unreachable!();
 }
 
-/// The function to handle a vote-addition event for the "delete_button"
-/// This function adds the vote then checks whether the goal is reached
-/// and then acts accordingly, either by deleting the message and then updating
-/// the announcement on the moderation channel or just by updating the announcement
-async fn handle_delete_vote(ctx: &Context, voter: User, message: &mut Message) {
+/// Answers the moderator who clicked a button of the voting with a message only they see. With
+/// `update` the answer replaces the confirmation prompt the button was on.
+async fn answer_vote(
+    ctx: &Context,
+    component: &MessageComponentInteraction,
+    update: bool,
+    content: String,
+) {
+    component
+        .create_interaction_response(&ctx.http, |r| {
+            r.kind(if update {
+                UpdateMessage
+            } else {
+                ChannelMessageWithSource
+            })
+            .interaction_response_data(|d| {
+                d.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
+                    .content(content)
+                    .components(|c| c)
+            })
+        })
+        .await
+        .unwrap();
+}
+
+/// Asks the moderator to confirm the vote that would carry out an action that is hard to undo
+async fn ask_confirmation(
+    ctx: &Context,
+    component: &MessageComponentInteraction,
+    voting_message_id: u64,
+    action: CouncilAction,
+) {
+    component
+        .create_interaction_response(&ctx.http, |r| {
+            r.kind(ChannelMessageWithSource)
+                .interaction_response_data(|d| {
+                    d.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
+                        .content(format!(
+                            "Äänesi ratkaisee äänestyksen, ja toimenpide \"{}\" tehdään heti. Vahvistatko äänesi?",
+                            action.label()
+                        ))
+                        .components(|c| {
+                            c.create_action_row(|r| {
+                                r.create_button(|b| {
+                                    b.label("Vahvista");
+                                    b.style(ButtonStyle::Danger);
+                                    b.custom_id(format!(
+                                        "confirm_vote_{}_{}",
+                                        voting_message_id,
+                                        action.id()
+                                    ))
+                                });
+                                r.create_button(|b| {
+                                    b.label("Peruuta");
+                                    b.style(ButtonStyle::Secondary);
+                                    b.custom_id("cancel_vote")
+                                })
+                            })
+                        })
+                })
+        })
+        .await
+        .unwrap();
+}
+
+/// Adds or takes back the vote of a moderator on an action of the council voting, and tells
+/// them the result. Once the action has enough votes it is carried out, after which its votes
+/// can no longer change. A vote that would carry out an action that needs confirmation is only
+/// cast once it is `confirmed` from the prompt, which the answer then replaces.
+async fn handle_council_vote(
+    ctx: &Context,
+    component: &MessageComponentInteraction,
+    voting_message_id: u64,
+    action: CouncilAction,
+    confirmed: bool,
+) {
     let db = ctx.get_db().await;
-    let event = db.get_voting_event(message.id.0).await.unwrap();
-    if event.delete_votes == event.delete_votes_required {
+    let voter = &component.user;
+    let event = db.get_voting_event(voting_message_id).await.unwrap();
+    if event.closed_at.is_some() {
+        answer_vote(
+            ctx,
+            component,
+            confirmed,
+            String::from("Äänestys on jo päättynyt"),
+        )
+        .await;
+        // Old votings may still show clickable buttons
+        update_voting_message(ctx, event.vote_message_id).await;
         return;
     }
-    if db
-        .add_vote(event.vote_message_id, voter.id.0, 0)
-        .await
-        .unwrap()
-        == 0
-    {
-        db.remove_vote(event.vote_message_id, voter.id.0, 0)
+    let thresholds = db.get_voting_thresholds(voting_message_id).await.unwrap();
+    let Some(threshold) = thresholds.iter().find(|t| t.vote_type == action.id()) else {
+        answer_vote(
+            ctx,
+            component,
+            confirmed,
+            format!(
+                "Toimenpide \"{}\" ei ole tarjolla tässä äänestyksessä",
+                action.label()
+            ),
+        )
+        .await;
+        return;
+    };
+    if threshold.executed_at.is_some() {
+        answer_vote(
+            ctx,
+            component,
+            confirmed,
+            format!("Toimenpide \"{}\" on jo tehty", action.label()),
+        )
+        .await;
+        return;
+    }
+    if action.needs_confirmation() && !confirmed {
+        let votes = db
+            .get_voting_event_votes(voting_message_id)
             .await
-            .unwrap();
-    } else {
-        let event = db.get_voting_event(message.id.0).await.unwrap();
-        if event.delete_votes == event.delete_votes_required {
-            let message = ctx
-                .http
-                .get_message(
-                    event.suspect_message_channel_id as u64,
-                    event.suspect_message_id as u64,
-                )
-                .await
-                .unwrap();
-            message.delete(&ctx.http).await.unwrap();
-            db.message_deleted(chrono::Local::now().naive_local(), event.vote_message_id)
-                .await
-                .unwrap();
+            .unwrap()
+            .into_iter()
+            .filter(|v| v.vote_type == action.id())
+            .collect::<Vec<_>>();
+        if !votes.iter().any(|v| v.voter_user_id == voter.id.0)
+            && votes.len() as i32 + 1 >= threshold.votes_required
+        {
+            ask_confirmation(ctx, component, voting_message_id, action).await;
+            return;
         }
     }
-    update_voting_message(ctx, event.vote_message_id as u64).await;
-}
 
-/// The function to handle a vote-addition event for the "ban_button"
-/// This function adds the vote then checks whether the goal is reached
-/// and then acts accordingly, either by banning the member and then updating
-/// the announcement on the moderation channel or just by updating the announcement
-//
-// NOTE: The ban actually only applies the "silenced" role upon the user
-async fn handle_silence_vote(ctx: &Context, voter: User, message: &mut Message) {
-    let db = ctx.get_db().await;
-    let event = db.get_voting_event(message.id.0).await.unwrap();
-    if event.silence_votes == event.silence_votes_required {
-        return;
-    }
-    if db
-        .add_vote(event.vote_message_id, voter.id.0, 1)
+    let (cast, votes) = db
+        .toggle_vote(event.vote_message_id, voter.id.0, action)
         .await
-        .unwrap()
-        == 0
-    {
-        db.remove_vote(event.vote_message_id, voter.id.0, 1)
+        .unwrap();
+    answer_vote(
+        ctx,
+        component,
+        confirmed,
+        format!(
+            "Äänesi toimenpiteelle \"{}\" {}. Ääniä {}/{}.",
+            action.label(),
+            if cast { "lisättiin" } else { "poistettiin" },
+            votes,
+            threshold.votes_required
+        ),
+    )
+    .await;
+    if cast
+        && votes >= threshold.votes_required as i64
+        && db
+            .mark_action_executed(event.vote_message_id, action)
             .await
-            .unwrap();
-    } else {
-        let event = db.get_voting_event(message.id.0).await.unwrap();
-        if event.silence_votes == event.silence_votes_required {
-            let guild_id: u64 = env::var("GUILD_ID")
-                .expect("Expected GUILD_ID in .env")
-                .parse()
-                .expect("Invalid GUILD_ID provided");
-            let silence_role: u64 = env::var("SILENCED_ROLE_ID")
-                .expect("Expected SILENCED_ROLE_ID in .env")
-                .parse()
-                .expect("Invalid SILENCED_ROLE_ID provided");
-            let mut member = ctx
-                .http
-                .get_member(guild_id, event.suspect_id as u64)
-                .await
-                .unwrap();
-            db.silence_user(member.user.id.0).await.ok(); // Might fail if the member is already silenced
-            member.add_role(&ctx.http, silence_role).await.ok();
-            member
-                .disable_communication_until_datetime(
-                    &ctx.http,
-                    Timestamp::from(chrono::Utc::now() + chrono::Duration::weeks(1)),
-                )
-                .await
-                .unwrap();
-            let rules_channel_id =
-                env::var("RULES_CHANNEL_ID").expect("RULES_CHANNEL_ID is not set");
-            if (member.user.dm(&ctx.http, |m| {
-                m.content(format!("Sinut on hiljennetty huonon käyttäytymisen vuoksi arvojäsenten toimesta.\n\nMikäli haluat keskusteluoikeutesi takaisin, voit olla yhteydessä Mastermindeihin joko yksityisviestitse tai sähköpostitse masterminds@testausserveri.fi. Tarkistathan sääntömme kanavalta <#{}>.", rules_channel_id))
-            }).await).is_err() {
-                info!("Unable to send \"Silenced notification\" to {}", member.user.id.0);
+            .unwrap()
+            > 0
+    {
+        let report = execute_action(ctx, action, &event, voter).await;
+        if report.succeeded() {
+            if let Some(outcome) = action.outcome() {
+                notify_reporters(ctx, &event, outcome).await;
             }
         }
     }
     update_voting_message(ctx, event.vote_message_id as u64).await;
 }
 
-/// This function handles the press off the "abuse_button"
-/// If the vote-goal is reached, the user will be given a
-/// role that prevents them from further abusing the reporting feature
-async fn handle_abuse_vote(ctx: &Context, voter: User, message: &mut Message) {
+/// Carries out an action the council has voted for and stores what came of it to be shown
+/// on the voting message. `voter` cast the deciding vote.
+async fn execute_action(
+    ctx: &Context,
+    action: CouncilAction,
+    event: &CouncilVoting,
+    voter: &User,
+) -> ActionReport {
+    let config = ctx.get_config().await;
     let db = ctx.get_db().await;
-    let event = db.get_voting_event(message.id.0).await.unwrap();
-    if event.block_reporter_votes == event.block_reporter_votes_required {
-        return;
+    let message_content = Some(event.suspect_message_content.clone());
+    // NOTE: Silencing doesn't ban the member, it only gives them the "silenced" role
+    let (effect, reason) = match action {
+        CouncilAction::Delete => (
+            Action::Delete {
+                user_id: event.suspect_id,
+                channel_id: event.suspect_message_channel_id,
+                message_id: event.suspect_message_id,
+            },
+            message_content,
+        ),
+        CouncilAction::Silence => (
+            Action::Silence {
+                user_id: event.suspect_id,
+                cause: String::from("huonon käyttäytymisen vuoksi arvojäsenten toimesta"),
+            },
+            message_content,
+        ),
+        CouncilAction::BlockReporter => (
+            Action::BlockReporter {
+                user_id: event.reporter_id,
+            },
+            Some(format!(
+                "Aiheeton ilmianto käyttäjän <@{}> viestistä",
+                event.suspect_id
+            )),
+        ),
+        CouncilAction::Timeout => (
+            Action::Timeout {
+                user_id: event.suspect_id,
+                until: chrono::Utc::now()
+                    + chrono::Duration::minutes(config.council_timeout_minutes),
+                notice: None,
+            },
+            message_content,
+        ),
+        CouncilAction::LockThread => (
+            Action::LockThread {
+                user_id: event.suspect_id,
+                channel_id: event.suspect_message_channel_id,
+            },
+            Some(format!(
+                "Ketju <#{}> lukittu viestin vuoksi: {}",
+                event.suspect_message_channel_id, event.suspect_message_content
+            )),
+        ),
+        CouncilAction::Kick => (
+            Action::Kick {
+                user_id: event.suspect_id,
+                audit_reason: String::from("Potkaistu arvojäsenten äänestyksellä"),
+            },
+            message_content,
+        ),
+    };
+    let report = moderation_action::execute(
+        ctx,
+        effect,
+        Some(voter.id.0),
+        ActionSource::CouncilVote,
+        reason,
+    )
+    .await;
+    if report.succeeded() && action == CouncilAction::Delete {
+        db.message_deleted(chrono::Local::now().naive_local(), event.vote_message_id)
+            .await
+            .ok();
     }
-    if db
-        .add_vote(event.vote_message_id, voter.id.0, 2)
+    if let Err(e) = db
+        .set_action_result(event.vote_message_id, action, &report.describe())
         .await
-        .unwrap()
-        == 0
     {
-        db.remove_vote(event.vote_message_id, voter.id.0, 2)
+        error!(
+            "Unable to store the result of {} on {}: {}",
+            action.as_str(),
+            event.vote_message_id,
+            e
+        );
+    }
+    report
+}
+
+/// Closes the voting without any action
+async fn handle_dismiss(ctx: &Context, voter: User, message: &mut Message) {
+    let db = ctx.get_db().await;
+    let event = db.get_voting_event(message.id.0).await.unwrap();
+    if event.resolution == VotingResolution::Pending.as_str()
+        && db
+            .close_voting(
+                event.vote_message_id,
+                VotingResolution::Dismissed,
+                Some(voter.id.0),
+            )
             .await
-            .unwrap();
-    } else {
-        let event = db.get_voting_event(message.id.0).await.unwrap();
-        if event.block_reporter_votes == event.block_reporter_votes_required {
-            let guild_id: u64 = env::var("GUILD_ID")
-                .expect("Expected GUILD_ID in .env")
-                .parse()
-                .expect("Invalid GUILD_ID provided");
-            let abuse_role: u64 = env::var("NO_REPORTS_ROLE_ID")
-                .expect("Expected NO_REPORTS_ROLE_ID in .env")
-                .parse()
-                .expect("Invalid NO_REPORTS_ROLE_ID provided");
-            let mut member = ctx
-                .http
-                .get_member(guild_id, event.reporter_id as u64)
+            .unwrap()
+            > 0
+    {
+        notify_reporters(ctx, &event, "ilmoitus hylättiin").await;
+    }
+    update_voting_message(ctx, event.vote_message_id).await;
+}
+
+/// Tells the reporters of the message who have turned the notifications on with `/reports`
+/// what came of their report. The voters are not named.
+async fn notify_reporters(ctx: &Context, event: &CouncilVoting, outcome: &str) {
+    let db = ctx.get_db().await;
+    let guild_id = ctx.get_config().await.guild_id;
+    let reporters = match db.get_reporters(event).await {
+        Ok(reporters) => reporters,
+        Err(e) => {
+            error!(
+                "Unable to fetch the reporters of voting {}: {}",
+                event.vote_message_id, e
+            );
+            return;
+        }
+    };
+    for reporter in reporters {
+        if !db.has_report_notifications(reporter).await.unwrap_or(false) {
+            continue;
+        }
+        let sent = match UserId(reporter).create_dm_channel(&ctx.http).await {
+            Ok(channel) => channel
+                .send_message(&ctx.http, |m| {
+                    m.content(format!(
+                        "Ilmiantamasi viesti on käsitelty: {}.\nhttps://discord.com/channels/{}/{}/{}\n\nVoit poistaa nämä ilmoitukset käytöstä komennolla `/reports notifications:False`.",
+                        outcome, guild_id, event.suspect_message_channel_id, event.suspect_message_id
+                    ))
+                })
                 .await
-                .unwrap();
-            member.add_role(&ctx.http, abuse_role).await.unwrap();
+                .is_ok(),
+            Err(_) => false,
+        };
+        if !sent {
+            info!("Unable to notify reporter {} of their report", reporter);
         }
     }
-    update_voting_message(ctx, event.vote_message_id as u64).await;
+}
+
+/// Sends the full edit history of the reported message as a text file only the moderator sees
+async fn handle_edit_history(ctx: &Context, component: &MessageComponentInteraction) {
+    send_edit_history(ctx, component, component.message.id.0).await;
+}
+
+/// Responds to `component` with the full edit history of the reported message of the voting
+pub async fn send_edit_history(
+    ctx: &Context,
+    component: &MessageComponentInteraction,
+    voting_message_id: u64,
+) {
+    let db = ctx.get_db().await;
+    let event = db.get_voting_event(voting_message_id).await.unwrap();
+    let edits = db
+        .get_voting_event_edits(event.vote_message_id)
+        .await
+        .unwrap();
+    let history = evidence::edit_history(
+        &event.suspect_message_content,
+        event.suspect_message_send_time,
+        &edits,
+    );
+    component
+        .create_interaction_response(&ctx.http, |r| {
+            r.kind(ChannelMessageWithSource)
+                .interaction_response_data(|d| {
+                    d.flags(InteractionApplicationCommandCallbackDataFlags::EPHEMERAL)
+                        .add_file(AttachmentType::Bytes {
+                            data: Cow::from(history.into_bytes()),
+                            filename: format!("muokkaushistoria_{}.txt", event.suspect_message_id),
+                        })
+                })
+        })
+        .await
+        .unwrap();
 }
 
 async fn handle_useless_button(ctx: &Context, component: &mut MessageComponentInteraction) {
@@ -542,30 +1200,52 @@ async fn handle_useless_button(ctx: &Context, component: &mut MessageComponentIn
 pub async fn handle_vote_interaction(ctx: &Context, interaction: Interaction) {
     if let Interaction::MessageComponent(mut component) = interaction {
         match component.data.custom_id.as_str() {
-            "delete_button" => {
-                info!("Delete vote by {}", component.user.tag());
-                handle_delete_vote(ctx, component.user.clone(), &mut component.message).await;
+            "useless_button" => {
+                handle_useless_button(ctx, &mut component).await;
             }
-            "ban_button" => {
-                info!("Ban vote by {}", component.user.tag());
-                handle_silence_vote(ctx, component.user.clone(), &mut component.message).await;
+            "edit_history_button" => {
+                handle_edit_history(ctx, &component).await;
             }
-            "abuse_button" => {
-                info!("Abuse vote by {}", component.user.tag());
-                handle_abuse_vote(ctx, component.user.clone(), &mut component.message).await;
+            "dismiss_button" => {
+                info!("Dismissal by {}", component.user.tag());
+                handle_dismiss(ctx, component.user.clone(), &mut component.message).await;
+                component
+                    .create_interaction_response(&ctx.http, |r| r.kind(DeferredUpdateMessage))
+                    .await
+                    .unwrap();
             }
-            "useless_button" => {
-                handle_useless_button(ctx, &mut component).await;
-                return;
+            "cancel_vote" => {
+                answer_vote(ctx, &component, true, String::from("Ääntä ei annettu")).await;
+            }
+            custom_id if custom_id.starts_with("confirm_vote_") => {
+                let Some((voting_message_id, action)) = custom_id
+                    .trim_start_matches("confirm_vote_")
+                    .split_once('_')
+                    .and_then(|(id, action)| {
+                        Some((
+                            id.parse().ok()?,
+                            CouncilAction::from_id(action.parse().ok()?)?,
+                        ))
+                    })
+                else {
+                    debug!("Invalid vote confirmation: {}", custom_id);
+                    return;
+                };
+                info!(
+                    "Confirmed {} vote by {}",
+                    action.as_str(),
+                    component.user.tag()
+                );
+                handle_council_vote(ctx, &component, voting_message_id, action, true).await;
             }
-            _ => {
-                debug!("Unknown interaction: {}", component.data.custom_id);
-                return;
+            custom_id => {
+                let Some(action) = CouncilAction::from_custom_id(custom_id) else {
+                    debug!("Unknown interaction: {}", custom_id);
+                    return;
+                };
+                info!("{} vote by {}", action.as_str(), component.user.tag());
+                handle_council_vote(ctx, &component, component.message.id.0, action, false).await;
             }
         }
-        component
-            .create_interaction_response(&ctx.http, |r| r.kind(DeferredUpdateMessage))
-            .await
-            .unwrap();
     }
 }